@@ -1,7 +1,20 @@
 use rockraft::config::Config as RockraftConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 
+pub mod watcher;
+
+pub use watcher::ConfigHandle;
+
+/// Current config schema version. Bump when making a breaking change to the
+/// TOML shape and add a branch to `migrate_to_current`.
+const CURRENT_CONFIG_VERSION: &str = "1";
+
+fn default_config_version() -> String {
+  CURRENT_CONFIG_VERSION.to_string()
+}
+
 /// Log configuration
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LogConfig {
@@ -35,9 +48,62 @@ pub struct Config {
   #[serde(default = "default_server_addr")]
   pub server_addr: String,
 
+  /// Optional WebSocket listening address. When set, the server also accepts
+  /// WebSocket connections carrying RESP-encoded commands as binary frames,
+  /// alongside the raw TCP listener on `server_addr`.
+  #[serde(default)]
+  pub ws_addr: Option<String>,
+
   /// Log configuration
   #[serde(default)]
   pub log: LogConfig,
+
+  /// Config schema version, used to migrate older files on load. Defaults to
+  /// the current version for files that predate this field.
+  #[serde(default = "default_config_version")]
+  pub version: String,
+
+  /// TLS settings for the RESP listener, absent when the server only
+  /// accepts plaintext connections.
+  #[serde(default)]
+  pub tls: Option<TlsSection>,
+
+  /// Password required by `AUTH password` (no username). When neither this
+  /// nor `users` is set, connections start already authenticated.
+  #[serde(default)]
+  pub requirepass: Option<String>,
+
+  /// Username/password table for `AUTH username password`. Empty by
+  /// default; `requirepass` and `users` can be configured independently.
+  #[serde(default)]
+  pub users: HashMap<String, String>,
+
+  /// Bulk strings at or above this many bytes are streamed out to TCP/TLS
+  /// clients in fixed-size chunks instead of being materialized as one
+  /// `Vec<u8>` first. Only governs outbound responses; WebSocket responses
+  /// are always sent as one encoded message.
+  #[serde(default = "default_streaming_threshold")]
+  pub streaming_threshold: usize,
+}
+
+fn default_streaming_threshold() -> usize {
+  1024 * 1024
+}
+
+/// `[tls]` config section: certificate/key for the RESP listener, with an
+/// optional client CA bundle to require and verify client certificates
+/// (mTLS).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TlsSection {
+  /// PEM-encoded certificate chain for the listener.
+  pub cert_path: String,
+  /// PEM-encoded private key matching `cert_path`.
+  pub key_path: String,
+  /// PEM-encoded CA bundle used to verify client certificates. When set,
+  /// clients must present a certificate signed by one of these CAs (mTLS);
+  /// when absent, the server only authenticates itself to the client.
+  #[serde(default)]
+  pub client_ca_path: Option<String>,
 }
 
 fn default_server_addr() -> String {
@@ -53,11 +119,23 @@ impl Config {
     let config: Config = toml::from_str(&config_str)
       .map_err(|e| format!("Failed to parse config file '{}': {}", path, e))?;
 
+    let config = Self::migrate_to_current(config);
+
     // Validate rockraft config
     config.raft.validate()?;
 
     Ok(config)
   }
+
+  /// Upgrade an older config schema version to the current one. There have
+  /// been no breaking schema changes since `version` was introduced, so this
+  /// only normalizes the field; add a match arm here when one is needed.
+  fn migrate_to_current(mut config: Config) -> Config {
+    if config.version != CURRENT_CONFIG_VERSION {
+      config.version = CURRENT_CONFIG_VERSION.to_string();
+    }
+    config
+  }
 }
 
 #[cfg(test)]
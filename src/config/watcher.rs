@@ -0,0 +1,127 @@
+//! Live configuration reload: watches the TOML config file for changes (and
+//! reacts to `SIGHUP`), atomically swapping in the subset of settings that
+//! are safe to change without a restart and rejecting edits to immutable
+//! ones (`raft.node_id`, `raft.rocksdb.data_path`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, info, warn};
+
+use super::Config;
+
+/// How often to check the config file's mtime for changes, in addition to
+/// reacting immediately to `SIGHUP`.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Shared handle to the current effective configuration. Command handlers
+/// and the server clone this freely and call `load()` to read the latest
+/// settings without needing a restart after a reload.
+#[derive(Clone)]
+pub struct ConfigHandle(Arc<ArcSwap<Config>>);
+
+impl ConfigHandle {
+  /// Wrap an already-loaded config for sharing.
+  pub fn new(config: Config) -> Self {
+    Self(Arc::new(ArcSwap::from_pointee(config)))
+  }
+
+  /// The current effective configuration.
+  pub fn load(&self) -> Arc<Config> {
+    self.0.load_full()
+  }
+
+  /// Re-read `path`, reject the reload if it touches an immutable field, and
+  /// otherwise atomically swap in the hot-reloadable settings.
+  fn reload(&self, path: &str) {
+    let current = self.0.load_full();
+
+    let candidate = match Config::from_file(path) {
+      Ok(c) => c,
+      Err(e) => {
+        error!("Config reload from '{}' failed: {}", path, e);
+        return;
+      }
+    };
+
+    if candidate.raft.node_id != current.raft.node_id {
+      error!(
+        "Rejecting config reload: raft.node_id is immutable (was {}, got {})",
+        current.raft.node_id, candidate.raft.node_id
+      );
+      return;
+    }
+
+    if candidate.raft.rocksdb.data_path != current.raft.rocksdb.data_path {
+      error!(
+        "Rejecting config reload: raft.rocksdb.data_path is immutable (was '{}', got '{}')",
+        current.raft.rocksdb.data_path, candidate.raft.rocksdb.data_path
+      );
+      return;
+    }
+
+    // Start from the current config and only overwrite the settings that are
+    // documented as safe to hot-reload; everything else, including the
+    // immutable fields just checked, keeps its original value.
+    let mut next = (*current).clone();
+    next.log = candidate.log;
+    next.version = candidate.version;
+
+    self.0.store(Arc::new(next));
+    info!("Configuration reloaded from '{}'", path);
+  }
+}
+
+/// Spawn the config-watcher task and return a `ConfigHandle` to its
+/// atomically-swapped configuration.
+pub fn spawn_watcher(config: Config, path: String) -> ConfigHandle {
+  let handle = ConfigHandle::new(config);
+  let task_handle = handle.clone();
+
+  tokio::spawn(async move {
+    let mut last_modified = file_mtime(&path);
+    let mut ticker = tokio::time::interval(WATCH_POLL_INTERVAL);
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+      Ok(sig) => Some(sig),
+      Err(e) => {
+        warn!("Failed to install SIGHUP handler for config reload: {}", e);
+        None
+      }
+    };
+
+    loop {
+      tokio::select! {
+        _ = ticker.tick() => {
+          let modified = file_mtime(&path);
+          if modified != last_modified {
+            last_modified = modified;
+            task_handle.reload(&path);
+          }
+        }
+        _ = wait_for_hangup(&mut hangup) => {
+          info!("Received SIGHUP, reloading configuration from '{}'", path);
+          last_modified = file_mtime(&path);
+          task_handle.reload(&path);
+        }
+      }
+    }
+  });
+
+  handle
+}
+
+fn file_mtime(path: &str) -> Option<std::time::SystemTime> {
+  std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+async fn wait_for_hangup(hangup: &mut Option<tokio::signal::unix::Signal>) {
+  match hangup {
+    Some(sig) => {
+      sig.recv().await;
+    }
+    None => std::future::pending().await,
+  }
+}
@@ -0,0 +1,14 @@
+//! Shared module tree for both of CoreDB's binaries: the default `coredb`
+//! binary (`src/main.rs`, the plain TCP server with `Store`, pub/sub,
+//! SCAN/KEYS/DEL, and persistence) and the opt-in `coredb-raft` binary
+//! (`src/bin/coredb-raft.rs`, the Raft-backed server with TLS/AUTH/RESP3/
+//! WebSocket/CLUSTER support). See `src/bin/coredb-raft.rs` for why these
+//! are two binaries instead of one.
+pub mod config;
+pub mod encoding;
+pub mod persistence;
+pub mod protocol;
+pub mod pubsub;
+pub mod server;
+pub mod store;
+pub mod util;
@@ -1,16 +1,10 @@
-mod config;
-mod encoding;
-mod protocol;
-mod server;
-mod util;
-
 use std::env;
-use std::sync::Arc;
 use tokio::signal;
+use tokio::sync::watch;
 use tracing::{error, info};
 
-use config::Config;
-use server::Server;
+use coredb::config::Config;
+use coredb::server::Server;
 
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
@@ -47,15 +41,15 @@ async fn main() -> std::io::Result<()> {
   };
 
   info!("Configuration loaded:");
-  info!("  node_id: {}", config.raft.node_id);
   info!("  server_addr: {}", config.server_addr);
-  info!("  raft_addr: {}", config.raft.raft.address);
   info!("  data_path: {}", config.raft.rocksdb.data_path);
-  info!("  single: {}", config.raft.raft.single);
-  info!("  join: {:?}", config.raft.raft.join);
 
-  // Create and start server (which creates Raft node internally)
-  let server = match Server::start(config).await {
+  // Bind the plain TCP server: in-memory `Store` with LRU/TTL eviction,
+  // pub/sub, SCAN/KEYS/DEL, and rkyv snapshot+AOF persistence restored from
+  // (and kept in) `data_path`. The Raft-backed server is a separate,
+  // explicitly opt-in binary — see `src/bin/coredb-raft.rs` — since it
+  // has none of those data-plane features beyond a single-key `DEL`.
+  let server = match Server::bind_with_data_dir(&config.server_addr, &config.raft.rocksdb.data_path).await {
     Ok(srv) => {
       info!("Server started successfully");
       info!("Listening on: {}", srv.local_addr());
@@ -67,12 +61,9 @@ async fn main() -> std::io::Result<()> {
     }
   };
 
-  // Clone server for signal handling
-  let server_for_shutdown = Arc::clone(&server);
-
-  // Spawn server in a separate task
-  let server_handle = tokio::spawn(async move {
-    server.run().await;
+  let (shutdown_tx, shutdown_rx) = watch::channel(false);
+  let server_task = tokio::spawn(async move {
+    server.run_until(shutdown_rx).await;
   });
 
   // Wait for Ctrl+C signal
@@ -86,14 +77,33 @@ async fn main() -> std::io::Result<()> {
     }
   }
 
-  // Shutdown Raft node
-  if let Err(e) = server_for_shutdown.shutdown().await {
-    error!("Error during shutdown: {}", e);
+  // Tell `run_until` to stop accepting connections and drain in-flight ones.
+  let _ = shutdown_tx.send(true);
+  if let Err(e) = server_task.await {
+    error!("Server task panicked during shutdown: {}", e);
   }
 
-  // Abort server task
-  server_handle.abort();
-
   info!("Server shutdown complete");
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Regression test for the entrypoint drift a prior fix (chunk2-1 wiring
+  /// the Raft server's module in, chunk1-4 pointing `main` at it) silently
+  /// introduced: this boots `Server` exactly the way `main` does, so if this
+  /// binary's `Server` import ever again resolves to a type whose
+  /// constructor doesn't match the call here, the build breaks instead of
+  /// shipping the wrong server under the `coredb` binary name.
+  #[tokio::test]
+  async fn main_entrypoint_boots_the_plain_server() {
+    let data_dir =
+      std::env::temp_dir().join(format!("coredb_main_entrypoint_test_{}", std::process::id()));
+    let server = Server::bind_with_data_dir("127.0.0.1:0", data_dir.to_str().unwrap())
+      .await
+      .unwrap();
+    assert!(server.local_addr().port() > 0);
+  }
+}
@@ -0,0 +1,301 @@
+//! Durable persistence for `Store`: a point-in-time snapshot plus an
+//! append-only command log, both encoded with rkyv so the on-disk format
+//! shares `StringValue`'s forward-compatible `version` field.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rkyv::{Archive, Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::encoding::StringValue;
+use crate::store::Store;
+
+/// Magic bytes identifying a CoreDB snapshot file.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"CRDB";
+/// Snapshot file format version.
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// One mutating operation recorded in the append-only command log, replayed
+/// in order against a restored snapshot on startup.
+#[derive(Debug, Clone, Archive, Serialize, Deserialize)]
+pub enum LogEntry {
+    /// `key` was set to the already-encoded `StringValue` bytes.
+    Set { key: String, encoded: Vec<u8> },
+    /// `key` was deleted.
+    Delete { key: String },
+}
+
+/// Write a full snapshot of `entries` (key, encoded `StringValue` pairs) to
+/// `path`, replacing any previous contents atomically via a temp-file rename.
+pub fn save_snapshot(path: &Path, entries: &[(String, Vec<u8>)]) -> io::Result<()> {
+    let payload = rkyv::to_bytes::<rkyv::rancor::Error>(&entries.to_vec())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(SNAPSHOT_MAGIC)?;
+        file.write_all(&[SNAPSHOT_VERSION])?;
+        file.write_all(&(payload.len() as u64).to_le_bytes())?;
+        file.write_all(&payload)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+/// Load a snapshot previously written by `save_snapshot`, skipping any entry
+/// whose `StringValue` has already expired.
+pub fn load_snapshot(path: &Path) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; 4];
+    file.read_exact(&mut header)?;
+    if &header != SNAPSHOT_MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "bad snapshot magic"));
+    }
+
+    let mut version = [0u8; 1];
+    file.read_exact(&mut version)?;
+    // `version` is read for forward compatibility; the current format is the
+    // only one understood today.
+    let _version = version[0];
+
+    let mut len_buf = [0u8; 8];
+    file.read_exact(&mut len_buf)?;
+    let len = u64::from_le_bytes(len_buf) as usize;
+
+    let mut payload = vec![0u8; len];
+    file.read_exact(&mut payload)?;
+
+    let entries: Vec<(String, Vec<u8>)> =
+        rkyv::from_bytes::<Vec<(String, Vec<u8>)>, rkyv::rancor::Error>(&payload)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    let now = crate::util::now_ms();
+    Ok(entries
+        .into_iter()
+        .filter(|(_, encoded)| match StringValue::deserialize(encoded) {
+            Ok(value) => !value.is_expired(now),
+            Err(_) => false,
+        })
+        .collect())
+}
+
+/// Append one log entry to the command log at `path`, as a length-prefixed
+/// rkyv frame.
+pub fn append_log_entry(path: &Path, entry: &LogEntry) -> io::Result<()> {
+    let payload = rkyv::to_bytes::<rkyv::rancor::Error>(entry)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&(payload.len() as u32).to_le_bytes())?;
+    file.write_all(&payload)?;
+    Ok(())
+}
+
+/// Replay every entry in the command log at `path` against `store`.
+pub fn replay_log(path: &Path, store: &Store) -> io::Result<usize> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let mut applied = 0;
+    loop {
+        let mut len_buf = [0u8; 4];
+        match file.read_exact(&mut len_buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+        let len = u32::from_le_bytes(len_buf) as usize;
+
+        let mut payload = vec![0u8; len];
+        if file.read_exact(&mut payload).is_err() {
+            // Truncated trailing frame (e.g. a crash mid-write): stop replaying.
+            break;
+        }
+
+        let entry = match rkyv::from_bytes::<LogEntry, rkyv::rancor::Error>(&payload) {
+            Ok(entry) => entry,
+            Err(_) => break,
+        };
+
+        match entry {
+            LogEntry::Set { key, encoded } => {
+                if let Ok(value) = StringValue::deserialize(&encoded) {
+                    if !value.is_expired(crate::util::now_ms()) {
+                        store.load_encoded(key, encoded);
+                    }
+                }
+            }
+            LogEntry::Delete { key } => {
+                let _ = store.remove(&key);
+            }
+        }
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+/// Background snapshot + append-only log subsystem for a `Store`.
+pub struct Persistence {
+    data_dir: PathBuf,
+    /// Trigger a snapshot (and log truncation) after this many writes.
+    snapshot_every_writes: u64,
+    writes_since_snapshot: AtomicU64,
+}
+
+impl Persistence {
+    pub fn new(data_dir: impl Into<PathBuf>, snapshot_every_writes: u64) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            snapshot_every_writes,
+            writes_since_snapshot: AtomicU64::new(0),
+        }
+    }
+
+    pub fn snapshot_path(&self) -> PathBuf {
+        self.data_dir.join("snapshot.rkyv")
+    }
+
+    pub fn log_path(&self) -> PathBuf {
+        self.data_dir.join("commands.log")
+    }
+
+    /// Load a prior snapshot (if any) into `store`, then replay the command
+    /// log tail on top of it.
+    pub fn restore(&self, store: &Store) -> io::Result<()> {
+        std::fs::create_dir_all(&self.data_dir)?;
+
+        let snapshot_path = self.snapshot_path();
+        if snapshot_path.exists() {
+            let entries = load_snapshot(&snapshot_path)?;
+            info!("Restored {} key(s) from snapshot", entries.len());
+            for (key, encoded) in entries {
+                store.load_encoded(key, encoded);
+            }
+        }
+
+        let replayed = replay_log(&self.log_path(), store)?;
+        if replayed > 0 {
+            info!("Replayed {} command(s) from the append-only log", replayed);
+        }
+
+        Ok(())
+    }
+
+    /// Record one mutating command and trigger a snapshot once enough writes
+    /// have accumulated.
+    pub fn record_write(&self, store: &Store, entry: LogEntry) {
+        if let Err(e) = append_log_entry(&self.log_path(), &entry) {
+            warn!("Failed to append to command log: {}", e);
+            return;
+        }
+
+        let writes = self.writes_since_snapshot.fetch_add(1, Ordering::Relaxed) + 1;
+        if writes >= self.snapshot_every_writes {
+            if let Err(e) = self.bgsave(store) {
+                warn!("Background snapshot failed: {}", e);
+            }
+        }
+    }
+
+    /// Snapshot the current keyspace to disk and truncate the command log,
+    /// since the snapshot now reflects every entry the log would replay.
+    pub fn bgsave(&self, store: &Store) -> io::Result<()> {
+        std::fs::create_dir_all(&self.data_dir)?;
+        let entries = store.snapshot_entries();
+        save_snapshot(&self.snapshot_path(), &entries)?;
+        File::create(self.log_path())?; // truncate
+        self.writes_since_snapshot.store(0, Ordering::Relaxed);
+        debug!("BGSAVE wrote {} key(s) to {:?}", entries.len(), self.snapshot_path());
+        Ok(())
+    }
+
+    /// Spawn a background task that snapshots on a fixed interval, in
+    /// addition to the write-count trigger in `record_write`.
+    pub fn spawn_periodic_snapshot(self: Arc<Self>, store: Arc<Store>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.bgsave(&store) {
+                    warn!("Periodic snapshot failed: {}", e);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = std::env::temp_dir().join(format!("coredb_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snapshot.rkyv");
+
+        let value = StringValue::new(b"hello".to_vec());
+        let entries = vec![("k".to_string(), value.serialize())];
+        save_snapshot(&path, &entries).unwrap();
+
+        let loaded = load_snapshot(&path).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0, "k");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_snapshot_skips_expired_entries() {
+        let dir = std::env::temp_dir().join(format!("coredb_test_exp_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snapshot.rkyv");
+
+        let expired = StringValue::with_expiration(b"old".to_vec(), 1); // far in the past
+        let entries = vec![("k".to_string(), expired.serialize())];
+        save_snapshot(&path, &entries).unwrap();
+
+        let loaded = load_snapshot(&path).unwrap();
+        assert!(loaded.is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_log_replay() {
+        let dir = std::env::temp_dir().join(format!("coredb_test_log_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("commands.log");
+
+        let value = StringValue::new(b"v1".to_vec());
+        append_log_entry(
+            &log_path,
+            &LogEntry::Set {
+                key: "a".to_string(),
+                encoded: value.serialize(),
+            },
+        )
+        .unwrap();
+        append_log_entry(&log_path, &LogEntry::Delete { key: "b".to_string() }).unwrap();
+
+        let store = Store::new();
+        store.set("b".to_string(), b"x".to_vec()).unwrap();
+        let applied = replay_log(&log_path, &store).unwrap();
+        assert_eq!(applied, 2);
+        assert_eq!(store.get("a").unwrap(), Some(b"v1".to_vec()));
+        assert_eq!(store.get("b").unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
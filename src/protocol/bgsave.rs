@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+
+use crate::protocol::command::Command;
+use crate::protocol::resp::Value;
+use crate::store::Store;
+
+/// BGSAVE command executor: snapshots the keyspace and truncates the command
+/// log, if persistence is configured. The snapshot is written before the
+/// reply is sent, not on a background task.
+pub struct BgsaveCmd;
+
+#[async_trait]
+impl Command for BgsaveCmd {
+    async fn execute(&self, _items: &[Value], store: &Store) -> Value {
+        match store.persistence() {
+            Some(persistence) => match persistence.bgsave(store) {
+                Ok(()) => Value::SimpleString("Background saving started".to_string()),
+                Err(e) => Value::error(format!("ERR {}", e)),
+            },
+            None => Value::error("ERR persistence is not configured for this server"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bgsave_without_persistence() {
+        let store = Store::new();
+        let items = vec![Value::BulkString(Some(b"BGSAVE".to_vec()))];
+        let result = BgsaveCmd.execute(&items, &store).await;
+        assert_eq!(
+            result,
+            Value::error("ERR persistence is not configured for this server")
+        );
+    }
+}
@@ -1,155 +1,103 @@
-use crate::protocol::get::GetCmd;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
 use crate::protocol::resp::Value;
-use crate::protocol::set::SetCmd;
 use crate::store::Store;
 
-/// Redis command types
-#[derive(Debug, Clone, PartialEq)]
-pub enum Command {
-    /// GET key
-    Get(GetCmd),
-    /// SET key value
-    Set(SetCmd),
-    /// Unknown or unsupported command
-    Unknown(String),
+/// Trait implemented by every RESP command handler.
+///
+/// `items` is the full command array (including the command name itself at
+/// index 0), mirroring how Redis commands are framed on the wire.
+#[async_trait]
+pub trait Command: Send + Sync {
+    async fn execute(&self, items: &[Value], store: &Store) -> Value;
 }
 
-impl Command {
-    /// Parse a RESP array into a Command
-    fn from_resp(value: Value) -> Option<Self> {
-        match value {
-            Value::Array(Some(items)) if !items.is_empty() => {
-                // First item should be the command name
-                let cmd_name = match &items[0] {
-                    Value::BulkString(Some(data)) => {
-                        String::from_utf8_lossy(data).to_uppercase()
-                    }
-                    Value::SimpleString(s) => s.to_uppercase(),
-                    _ => return Some(Command::Unknown("invalid command format".to_string())),
-                };
-
-                match cmd_name.as_str() {
-                    "GET" => GetCmd::parse(&items),
-                    "SET" => SetCmd::parse(&items),
-                    _ => Some(Command::Unknown(format!("unknown command '{}'", cmd_name))),
-                }
-            }
-            _ => None,
+/// Registry mapping command names (case-insensitive) to their handlers.
+pub struct CommandFactory {
+    commands: HashMap<String, Box<dyn Command>>,
+}
+
+impl CommandFactory {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self {
+            commands: HashMap::new(),
         }
     }
 
-    /// Execute the command on the given store and return the response
-    fn execute_internal(&self, store: &Store) -> Value {
-        match self {
-            Command::Get(cmd) => cmd.execute(store),
-            Command::Set(cmd) => cmd.execute(store),
-            Command::Unknown(msg) => Value::error(msg.clone()),
-        }
+    /// Register a command handler under `name` (stored upper-cased).
+    pub fn register(&mut self, name: &str, cmd: impl Command + 'static) {
+        self.commands.insert(name.to_uppercase(), Box::new(cmd));
     }
 
-    /// Parse and execute a RESP command on the given store
-    pub fn execute(value: Value, store: &Store) -> Value {
-        match Self::from_resp(value) {
-            Some(cmd) => cmd.execute_internal(store),
-            None => Value::error("ERR failed to parse command"),
+    /// Parse `value` as a command array and dispatch it to the matching
+    /// registered handler.
+    pub async fn execute(&self, value: Value, store: &Store) -> Value {
+        let items = match value {
+            Value::Array(Some(items)) if !items.is_empty() => items,
+            Value::Array(Some(_)) => return Value::error("ERR empty command"),
+            _ => return Value::error("ERR failed to parse command"),
+        };
+
+        let name = match &items[0] {
+            Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_uppercase(),
+            Value::SimpleString(s) => s.to_uppercase(),
+            _ => return Value::error("ERR invalid command format"),
+        };
+
+        match self.commands.get(&name) {
+            Some(cmd) => cmd.execute(&items, store).await,
+            None => Value::error(format!("ERR unknown command '{}'", name)),
         }
     }
 }
 
+impl Default for CommandFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::store::Store;
-
-    #[test]
-    fn test_parse_get_command() {
-        let resp = Value::Array(Some(vec![
-            Value::BulkString(Some(b"GET".to_vec())),
-            Value::BulkString(Some(b"mykey".to_vec())),
-        ]));
-
-        let cmd = Command::from_resp(resp).unwrap();
-        match cmd {
-            Command::Get(get_cmd) => {
-                assert_eq!(get_cmd.key, "mykey");
-            }
-            _ => panic!("Expected GET command"),
-        }
-    }
-
-    #[test]
-    fn test_parse_set_command() {
-        let resp = Value::Array(Some(vec![
-            Value::BulkString(Some(b"SET".to_vec())),
-            Value::BulkString(Some(b"mykey".to_vec())),
-            Value::BulkString(Some(b"myvalue".to_vec())),
-        ]));
-
-        let cmd = Command::from_resp(resp).unwrap();
-        match cmd {
-            Command::Set(set_cmd) => {
-                assert_eq!(set_cmd.key, "mykey");
-                assert_eq!(set_cmd.value, b"myvalue");
-            }
-            _ => panic!("Expected SET command"),
-        }
-    }
+    use crate::protocol::get::GetCmd;
+    use crate::protocol::set::SetCmd;
 
-    #[test]
-    fn test_execute_get_not_found() {
+    #[tokio::test]
+    async fn test_dispatch_unknown_command() {
         let store = Store::new();
-        let resp = Value::Array(Some(vec![
-            Value::BulkString(Some(b"GET".to_vec())),
-            Value::BulkString(Some(b"nonexistent".to_vec())),
-        ]));
+        let mut factory = CommandFactory::new();
+        factory.register("GET", GetCmd);
 
-        let result = Command::execute(resp, &store);
-        
-        assert_eq!(result, Value::BulkString(None));
+        let resp = Value::Array(Some(vec![Value::BulkString(Some(b"UNKNOWN".to_vec()))]));
+        let result = factory.execute(resp, &store).await;
+        assert_eq!(result, Value::error("ERR unknown command 'UNKNOWN'"));
     }
 
-    #[test]
-    fn test_execute_set_and_get() {
+    #[tokio::test]
+    async fn test_dispatch_set_then_get() {
         let store = Store::new();
-        
-        // SET
+        let mut factory = CommandFactory::new();
+        factory.register("GET", GetCmd);
+        factory.register("SET", SetCmd);
+
         let set_resp = Value::Array(Some(vec![
             Value::BulkString(Some(b"SET".to_vec())),
             Value::BulkString(Some(b"mykey".to_vec())),
             Value::BulkString(Some(b"myvalue".to_vec())),
         ]));
-        let set_result = Command::execute(set_resp, &store);
-        assert_eq!(set_result, Value::ok());
-        
-        // GET
+        assert_eq!(factory.execute(set_resp, &store).await, Value::ok());
+
         let get_resp = Value::Array(Some(vec![
             Value::BulkString(Some(b"GET".to_vec())),
             Value::BulkString(Some(b"mykey".to_vec())),
         ]));
-        let get_result = Command::execute(get_resp, &store);
-        assert_eq!(get_result, Value::BulkString(Some(b"myvalue".to_vec())));
-    }
-
-    #[test]
-    fn test_execute_invalid_command() {
-        let store = Store::new();
-        let resp = Value::Array(Some(vec![
-            Value::BulkString(Some(b"UNKNOWN".to_vec())),
-        ]));
-
-        let result = Command::execute(resp, &store);
-        
-        assert_eq!(result, Value::error("unknown command 'UNKNOWN'"));
-    }
-
-    #[test]
-    fn test_execute_parse_error() {
-        let store = Store::new();
-        // Invalid RESP (not an array)
-        let resp = Value::SimpleString("not a command".to_string());
-
-        let result = Command::execute(resp, &store);
-        
-        assert_eq!(result, Value::error("ERR failed to parse command"));
+        assert_eq!(
+            factory.execute(get_resp, &store).await,
+            Value::BulkString(Some(b"myvalue".to_vec()))
+        );
     }
 }
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+
+use crate::protocol::command::Command;
+use crate::protocol::resp::Value;
+use crate::store::Store;
+
+/// KEYS pattern command executor: lists every live key matching a glob pattern.
+pub struct KeysCmd;
+
+#[async_trait]
+impl Command for KeysCmd {
+    async fn execute(&self, items: &[Value], store: &Store) -> Value {
+        if items.len() != 2 {
+            return Value::error("ERR wrong number of arguments for 'keys' command");
+        }
+
+        let pattern = match &items[1] {
+            Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_string(),
+            Value::SimpleString(s) => s.clone(),
+            _ => return Value::error("ERR invalid pattern"),
+        };
+
+        match store.keys(&pattern) {
+            Ok(keys) => Value::Array(Some(
+                keys.into_iter()
+                    .map(|k| Value::BulkString(Some(k.into_bytes())))
+                    .collect(),
+            )),
+            Err(e) => Value::error(format!("ERR {}", e)),
+        }
+    }
+}
+
+/// DEL key [key ...] command executor: deletes keys unconditionally,
+/// returning how many were actually present.
+pub struct DelCmd;
+
+#[async_trait]
+impl Command for DelCmd {
+    async fn execute(&self, items: &[Value], store: &Store) -> Value {
+        if items.len() < 2 {
+            return Value::error("ERR wrong number of arguments for 'del' command");
+        }
+
+        let keys: Vec<String> = items[1..]
+            .iter()
+            .filter_map(|item| match item {
+                Value::BulkString(Some(data)) => Some(String::from_utf8_lossy(data).to_string()),
+                Value::SimpleString(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+
+        match store.delete_many(&keys) {
+            Ok(deleted) => Value::Integer(deleted as i64),
+            Err(e) => Value::error(format!("ERR {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_keys_cmd() {
+        let store = Store::new();
+        store.set("a:1".to_string(), b"x".to_vec()).unwrap();
+        store.set("b:1".to_string(), b"x".to_vec()).unwrap();
+
+        let items = vec![
+            Value::BulkString(Some(b"KEYS".to_vec())),
+            Value::BulkString(Some(b"a:*".to_vec())),
+        ];
+        let result = KeysCmd.execute(&items, &store).await;
+        assert_eq!(
+            result,
+            Value::Array(Some(vec![Value::BulkString(Some(b"a:1".to_vec()))]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_del_cmd() {
+        let store = Store::new();
+        store.set("a".to_string(), b"x".to_vec()).unwrap();
+        store.set("b".to_string(), b"x".to_vec()).unwrap();
+
+        let items = vec![
+            Value::BulkString(Some(b"DEL".to_vec())),
+            Value::BulkString(Some(b"a".to_vec())),
+            Value::BulkString(Some(b"missing".to_vec())),
+            Value::BulkString(Some(b"b".to_vec())),
+        ];
+        let result = DelCmd.execute(&items, &store).await;
+        assert_eq!(result, Value::Integer(2));
+    }
+
+    #[tokio::test]
+    async fn test_del_wrong_args() {
+        let store = Store::new();
+        let items = vec![Value::BulkString(Some(b"DEL".to_vec()))];
+        let result = DelCmd.execute(&items, &store).await;
+        assert_eq!(
+            result,
+            Value::error("ERR wrong number of arguments for 'del' command")
+        );
+    }
+}
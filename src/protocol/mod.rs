@@ -3,9 +3,13 @@
 //! This module provides RESP (REdis Serialization Protocol) parsing and
 //! Redis command handling.
 
+pub mod bgsave;
 pub mod command;
 pub mod get;
+pub mod keys;
+pub mod publish;
 pub mod resp;
+pub mod scan;
 pub mod set;
 
 pub use command::CommandFactory;
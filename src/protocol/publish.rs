@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+
+use crate::protocol::command::Command;
+use crate::protocol::resp::Value;
+use crate::store::Store;
+
+/// PUBLISH command executor: fans a message out to a channel's subscribers
+/// (exact and pattern matches) and reports how many received it.
+pub struct PublishCmd;
+
+#[async_trait]
+impl Command for PublishCmd {
+    async fn execute(&self, items: &[Value], store: &Store) -> Value {
+        if items.len() != 3 {
+            return Value::error("ERR wrong number of arguments for 'publish' command");
+        }
+
+        let channel = match &items[1] {
+            Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_string(),
+            Value::SimpleString(s) => s.clone(),
+            _ => return Value::error("ERR invalid channel name"),
+        };
+
+        let payload = match &items[2] {
+            Value::BulkString(Some(data)) => data.clone(),
+            Value::SimpleString(s) => s.as_bytes().to_vec(),
+            _ => return Value::error("ERR invalid message"),
+        };
+
+        let delivered = store.pubsub().publish(&channel, payload);
+        Value::Integer(delivered as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_no_subscribers() {
+        let store = Store::new();
+        let items = vec![
+            Value::BulkString(Some(b"PUBLISH".to_vec())),
+            Value::BulkString(Some(b"ch".to_vec())),
+            Value::BulkString(Some(b"hello".to_vec())),
+        ];
+        let result = PublishCmd.execute(&items, &store).await;
+        assert_eq!(result, Value::Integer(0));
+    }
+
+    #[tokio::test]
+    async fn test_publish_wrong_args() {
+        let store = Store::new();
+        let items = vec![Value::BulkString(Some(b"PUBLISH".to_vec()))];
+        let result = PublishCmd.execute(&items, &store).await;
+        assert_eq!(
+            result,
+            Value::error("ERR wrong number of arguments for 'publish' command")
+        );
+    }
+}
@@ -1,4 +1,8 @@
 /// RESP (REdis Serialization Protocol) data types
+///
+/// The first five variants are RESP2; the rest are RESP3-only and are
+/// downgraded to an equivalent RESP2 representation by `encode_resp` when
+/// the connection hasn't negotiated RESP3 via `HELLO`.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
   /// Simple strings, used for simple responses like "OK"
@@ -11,6 +15,27 @@ pub enum Value {
   BulkString(Option<Vec<u8>>),
   /// Arrays of other values (can be null)
   Array(Option<Vec<Value>>),
+  /// RESP3 null (`_\r\n`); downgrades to `$-1\r\n` on RESP2.
+  Null,
+  /// RESP3 double (`,`); downgrades to a bulk string on RESP2.
+  Double(f64),
+  /// RESP3 boolean (`#t`/`#f`); downgrades to `Integer(1)`/`Integer(0)` on RESP2.
+  Boolean(bool),
+  /// RESP3 big number (`(`), carried as its decimal string; downgrades to a
+  /// bulk string on RESP2.
+  BigNumber(String),
+  /// RESP3 bulk error (`!`); downgrades to a simple `Error` on RESP2.
+  BulkError(String),
+  /// RESP3 verbatim string (`=`), with a 3-byte format marker (e.g. `txt`)
+  /// and its payload; downgrades to a plain bulk string (format dropped) on RESP2.
+  VerbatimString(String, Vec<u8>),
+  /// RESP3 map (`%`), as interleaved key/value pairs; downgrades to a flat
+  /// `Array` on RESP2.
+  Map(Vec<(Value, Value)>),
+  /// RESP3 set (`~`); downgrades to an `Array` on RESP2.
+  Set(Vec<Value>),
+  /// RESP3 out-of-band push message (`>`); downgrades to an `Array` on RESP2.
+  Push(Vec<Value>),
 }
 
 impl Value {
@@ -24,14 +49,22 @@ impl Value {
     Value::Error(msg.into())
   }
 
-  /// Encode Value to RESP bytes
+  /// Encode as RESP2, the protocol every connection starts in.
   pub fn encode(&self) -> Vec<u8> {
+    self.encode_resp(2)
+  }
+
+  /// Encode according to the connection's negotiated protocol version
+  /// (`2` or `3`, see `HELLO`). RESP3-only variants are downgraded to their
+  /// RESP2 equivalent when `protocol` is `2`.
+  pub fn encode_resp(&self, protocol: u8) -> Vec<u8> {
     let mut buf = Vec::new();
-    self.encode_to(&mut buf);
+    self.encode_to(&mut buf, protocol);
     buf
   }
 
-  fn encode_to(&self, buf: &mut Vec<u8>) {
+  fn encode_to(&self, buf: &mut Vec<u8>, protocol: u8) {
+    let resp3 = protocol >= 3;
     match self {
       Value::SimpleString(s) => {
         buf.push(b'+');
@@ -66,123 +99,470 @@ impl Value {
         buf.extend_from_slice(items.len().to_string().as_bytes());
         buf.extend_from_slice(b"\r\n");
         for item in items {
-          item.encode_to(buf);
+          item.encode_to(buf, protocol);
+        }
+      }
+      Value::Null => {
+        if resp3 {
+          buf.extend_from_slice(b"_\r\n");
+        } else {
+          buf.extend_from_slice(b"$-1\r\n");
+        }
+      }
+      Value::Double(d) => {
+        if resp3 {
+          buf.push(b',');
+          buf.extend_from_slice(d.to_string().as_bytes());
+          buf.extend_from_slice(b"\r\n");
+        } else {
+          Value::BulkString(Some(d.to_string().into_bytes())).encode_to(buf, protocol);
+        }
+      }
+      Value::Boolean(b) => {
+        if resp3 {
+          buf.extend_from_slice(if *b { b"#t\r\n" } else { b"#f\r\n" });
+        } else {
+          Value::Integer(if *b { 1 } else { 0 }).encode_to(buf, protocol);
+        }
+      }
+      Value::BigNumber(digits) => {
+        if resp3 {
+          buf.push(b'(');
+          buf.extend_from_slice(digits.as_bytes());
+          buf.extend_from_slice(b"\r\n");
+        } else {
+          Value::BulkString(Some(digits.clone().into_bytes())).encode_to(buf, protocol);
+        }
+      }
+      Value::BulkError(msg) => {
+        if resp3 {
+          buf.push(b'!');
+          buf.extend_from_slice(msg.len().to_string().as_bytes());
+          buf.extend_from_slice(b"\r\n");
+          buf.extend_from_slice(msg.as_bytes());
+          buf.extend_from_slice(b"\r\n");
+        } else {
+          Value::Error(msg.clone()).encode_to(buf, protocol);
+        }
+      }
+      Value::VerbatimString(format, data) => {
+        if resp3 {
+          buf.push(b'=');
+          buf.extend_from_slice((data.len() + 4).to_string().as_bytes());
+          buf.extend_from_slice(b"\r\n");
+          buf.extend_from_slice(format.as_bytes());
+          buf.push(b':');
+          buf.extend_from_slice(data);
+          buf.extend_from_slice(b"\r\n");
+        } else {
+          Value::BulkString(Some(data.clone())).encode_to(buf, protocol);
+        }
+      }
+      Value::Map(pairs) => {
+        if resp3 {
+          buf.push(b'%');
+          buf.extend_from_slice(pairs.len().to_string().as_bytes());
+          buf.extend_from_slice(b"\r\n");
+          for (k, v) in pairs {
+            k.encode_to(buf, protocol);
+            v.encode_to(buf, protocol);
+          }
+        } else {
+          buf.push(b'*');
+          buf.extend_from_slice((pairs.len() * 2).to_string().as_bytes());
+          buf.extend_from_slice(b"\r\n");
+          for (k, v) in pairs {
+            k.encode_to(buf, protocol);
+            v.encode_to(buf, protocol);
+          }
+        }
+      }
+      Value::Set(items) => {
+        buf.push(if resp3 { b'~' } else { b'*' });
+        buf.extend_from_slice(items.len().to_string().as_bytes());
+        buf.extend_from_slice(b"\r\n");
+        for item in items {
+          item.encode_to(buf, protocol);
+        }
+      }
+      Value::Push(items) => {
+        buf.push(if resp3 { b'>' } else { b'*' });
+        buf.extend_from_slice(items.len().to_string().as_bytes());
+        buf.extend_from_slice(b"\r\n");
+        for item in items {
+          item.encode_to(buf, protocol);
         }
       }
     }
   }
 }
 
+/// Outcome of attempting to parse one frame out of a buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseResult {
+  /// A full value was parsed; `consumed` bytes should be dropped from the buffer.
+  Complete(Value, usize),
+  /// The buffer ends mid-frame; the caller should keep accumulating bytes
+  /// and retry once more data has arrived.
+  Incomplete,
+  /// The buffer contains malformed input that can never become a valid
+  /// frame; the connection should be sent this protocol error and closed.
+  Invalid(String),
+}
+
 /// Parser for RESP protocol
 pub struct Parser;
 
+/// Intermediate result of a sub-parser: `Done` with the parsed value, or
+/// `Incomplete` ("need more data") / `Invalid` ("malformed input")
+/// propagated up to `parse_frame`.
+enum Step<T> {
+  Done(T),
+  Incomplete,
+  Invalid(String),
+}
+
+macro_rules! try_step {
+  ($e:expr) => {
+    match $e {
+      Step::Done(v) => v,
+      Step::Incomplete => return Step::Incomplete,
+      Step::Invalid(msg) => return Step::Invalid(msg),
+    }
+  };
+}
+
 impl Parser {
-  /// Parse RESP data from buffer, return (Value, consumed_bytes) if successful
+  /// Parse RESP data from buffer.
+  ///
+  /// Kept for backwards compatibility with callers that only care about a
+  /// successfully parsed frame; prefer `parse_frame` to distinguish
+  /// incomplete input from a protocol error.
   pub fn parse(buffer: &[u8]) -> Option<(Value, usize)> {
+    match Self::parse_frame(buffer) {
+      ParseResult::Complete(value, consumed) => Some((value, consumed)),
+      ParseResult::Incomplete | ParseResult::Invalid(_) => None,
+    }
+  }
+
+  /// Parse one RESP frame (or inline command) out of `buffer`, distinguishing
+  /// a complete frame from a buffer that simply needs more bytes or one that
+  /// is irrecoverably malformed.
+  pub fn parse_frame(buffer: &[u8]) -> ParseResult {
     if buffer.is_empty() {
-      return None;
+      return ParseResult::Incomplete;
     }
 
     let mut pos = 0;
-    let result = Self::parse_value(buffer, &mut pos)?;
-    Some((result, pos))
+    match Self::parse_value(buffer, &mut pos) {
+      Step::Done(value) => ParseResult::Complete(value, pos),
+      Step::Incomplete => ParseResult::Incomplete,
+      Step::Invalid(msg) => ParseResult::Invalid(msg),
+    }
   }
 
-  fn parse_value(buffer: &[u8], pos: &mut usize) -> Option<Value> {
+  fn parse_value(buffer: &[u8], pos: &mut usize) -> Step<Value> {
     if *pos >= buffer.len() {
-      return None;
+      return Step::Incomplete;
     }
 
     let type_byte = buffer[*pos];
-    *pos += 1;
 
     match type_byte {
-      b'+' => Self::parse_simple_string(buffer, pos),
-      b'-' => Self::parse_error(buffer, pos),
-      b':' => Self::parse_integer(buffer, pos),
-      b'$' => Self::parse_bulk_string(buffer, pos),
-      b'*' => Self::parse_array(buffer, pos),
-      _ => None,
+      b'+' | b'-' | b':' | b'$' | b'*' | b'_' | b',' | b'#' | b'(' | b'!' | b'=' | b'%' | b'~'
+      | b'>' => {
+        *pos += 1;
+        match type_byte {
+          b'+' => Self::parse_simple_string(buffer, pos),
+          b'-' => Self::parse_error(buffer, pos),
+          b':' => Self::parse_integer(buffer, pos),
+          b'$' => Self::parse_bulk_string(buffer, pos),
+          b'*' => Self::parse_array(buffer, pos),
+          b'_' => Self::parse_null(buffer, pos),
+          b',' => Self::parse_double(buffer, pos),
+          b'#' => Self::parse_boolean(buffer, pos),
+          b'(' => Self::parse_big_number(buffer, pos),
+          b'!' => Self::parse_bulk_error(buffer, pos),
+          b'=' => Self::parse_verbatim_string(buffer, pos),
+          b'%' => Self::parse_map(buffer, pos),
+          b'~' => Self::parse_set(buffer, pos),
+          b'>' => Self::parse_push(buffer, pos),
+          _ => unreachable!(),
+        }
+      }
+      // Anything else is treated as a legacy inline command: a single
+      // whitespace-separated, newline-terminated line.
+      _ => Self::parse_inline(buffer, pos),
     }
   }
 
-  fn parse_simple_string(buffer: &[u8], pos: &mut usize) -> Option<Value> {
-    let line = Self::read_line(buffer, pos)?;
-    Some(Value::SimpleString(
+  fn parse_simple_string(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    Step::Done(Value::SimpleString(
       String::from_utf8_lossy(line).to_string(),
     ))
   }
 
-  fn parse_error(buffer: &[u8], pos: &mut usize) -> Option<Value> {
-    let line = Self::read_line(buffer, pos)?;
-    Some(Value::Error(String::from_utf8_lossy(line).to_string()))
+  fn parse_error(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    Step::Done(Value::Error(String::from_utf8_lossy(line).to_string()))
   }
 
-  fn parse_integer(buffer: &[u8], pos: &mut usize) -> Option<Value> {
-    let line = Self::read_line(buffer, pos)?;
-    let num = String::from_utf8_lossy(line).parse::<i64>().ok()?;
-    Some(Value::Integer(num))
+  fn parse_integer(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    match String::from_utf8_lossy(line).parse::<i64>() {
+      Ok(num) => Step::Done(Value::Integer(num)),
+      Err(_) => Step::Invalid("ERR Protocol error: invalid integer".to_string()),
+    }
   }
 
-  fn parse_bulk_string(buffer: &[u8], pos: &mut usize) -> Option<Value> {
-    let line = Self::read_line(buffer, pos)?;
-    let len = String::from_utf8_lossy(line).parse::<i64>().ok()?;
+  fn parse_bulk_string(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    let len = match String::from_utf8_lossy(line).parse::<i64>() {
+      Ok(len) => len,
+      Err(_) => {
+        return Step::Invalid("ERR Protocol error: invalid bulk length".to_string());
+      }
+    };
 
     if len == -1 {
-      return Some(Value::BulkString(None));
+      return Step::Done(Value::BulkString(None));
     }
 
     if len < 0 {
-      return None;
+      return Step::Invalid("ERR Protocol error: invalid bulk length".to_string());
     }
 
     let len = len as usize;
 
-    // Check if we have enough data (len + \r\n)
+    // A bulk-string length prefix parsed fine, but the payload (plus the
+    // trailing \r\n) may not have arrived yet -- that's incomplete, not invalid.
     if *pos + len + 2 > buffer.len() {
-      return None;
+      return Step::Incomplete;
     }
 
     let data = buffer[*pos..*pos + len].to_vec();
     *pos += len + 2; // +2 for \r\n
 
-    Some(Value::BulkString(Some(data)))
+    Step::Done(Value::BulkString(Some(data)))
   }
 
-  fn parse_array(buffer: &[u8], pos: &mut usize) -> Option<Value> {
-    let line = Self::read_line(buffer, pos)?;
-    let count = String::from_utf8_lossy(line).parse::<i64>().ok()?;
+  fn parse_array(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    let count = match String::from_utf8_lossy(line).parse::<i64>() {
+      Ok(count) => count,
+      Err(_) => {
+        return Step::Invalid("ERR Protocol error: invalid multibulk length".to_string());
+      }
+    };
 
     if count == -1 {
-      return Some(Value::Array(None));
+      return Step::Done(Value::Array(None));
     }
 
     if count < 0 {
-      return None;
+      return Step::Invalid("ERR Protocol error: invalid multibulk length".to_string());
     }
 
     let count = count as usize;
     let mut items = Vec::with_capacity(count);
 
     for _ in 0..count {
-      items.push(Self::parse_value(buffer, pos)?);
+      items.push(try_step!(Self::parse_value(buffer, pos)));
     }
 
-    Some(Value::Array(Some(items)))
+    Step::Done(Value::Array(Some(items)))
+  }
+
+  fn parse_null(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    try_step!(Self::read_line(buffer, pos));
+    Step::Done(Value::Null)
   }
 
-  fn read_line<'a>(buffer: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+  fn parse_double(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    match String::from_utf8_lossy(line).parse::<f64>() {
+      Ok(d) => Step::Done(Value::Double(d)),
+      Err(_) => Step::Invalid("ERR Protocol error: invalid double".to_string()),
+    }
+  }
+
+  fn parse_boolean(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    match line {
+      b"t" => Step::Done(Value::Boolean(true)),
+      b"f" => Step::Done(Value::Boolean(false)),
+      _ => Step::Invalid("ERR Protocol error: invalid boolean".to_string()),
+    }
+  }
+
+  fn parse_big_number(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    let text = String::from_utf8_lossy(line).to_string();
+    let digits = text.strip_prefix('-').unwrap_or(&text);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+      return Step::Invalid("ERR Protocol error: invalid big number".to_string());
+    }
+    Step::Done(Value::BigNumber(text))
+  }
+
+  fn parse_bulk_error(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    match Self::parse_bulk_string(buffer, pos) {
+      Step::Done(Value::BulkString(Some(data))) => {
+        Step::Done(Value::BulkError(String::from_utf8_lossy(&data).to_string()))
+      }
+      Step::Done(Value::BulkString(None)) => {
+        Step::Invalid("ERR Protocol error: invalid bulk error length".to_string())
+      }
+      Step::Done(_) => unreachable!(),
+      Step::Incomplete => Step::Incomplete,
+      Step::Invalid(msg) => Step::Invalid(msg),
+    }
+  }
+
+  fn parse_verbatim_string(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    match Self::parse_bulk_string(buffer, pos) {
+      Step::Done(Value::BulkString(Some(data))) => {
+        if data.len() < 4 || data[3] != b':' {
+          return Step::Invalid("ERR Protocol error: invalid verbatim string".to_string());
+        }
+        let format = String::from_utf8_lossy(&data[..3]).to_string();
+        Step::Done(Value::VerbatimString(format, data[4..].to_vec()))
+      }
+      Step::Done(Value::BulkString(None)) => {
+        Step::Invalid("ERR Protocol error: invalid verbatim string length".to_string())
+      }
+      Step::Done(_) => unreachable!(),
+      Step::Incomplete => Step::Incomplete,
+      Step::Invalid(msg) => Step::Invalid(msg),
+    }
+  }
+
+  fn parse_map(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+    let count = match String::from_utf8_lossy(line).parse::<i64>() {
+      Ok(count) if count >= 0 => count as usize,
+      _ => return Step::Invalid("ERR Protocol error: invalid map length".to_string()),
+    };
+
+    let mut pairs = Vec::with_capacity(count);
+    for _ in 0..count {
+      let key = try_step!(Self::parse_value(buffer, pos));
+      let value = try_step!(Self::parse_value(buffer, pos));
+      pairs.push((key, value));
+    }
+    Step::Done(Value::Map(pairs))
+  }
+
+  fn parse_set(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    match Self::parse_array(buffer, pos) {
+      Step::Done(Value::Array(Some(items))) => Step::Done(Value::Set(items)),
+      Step::Done(Value::Array(None)) => {
+        Step::Invalid("ERR Protocol error: invalid set length".to_string())
+      }
+      Step::Done(_) => unreachable!(),
+      Step::Incomplete => Step::Incomplete,
+      Step::Invalid(msg) => Step::Invalid(msg),
+    }
+  }
+
+  fn parse_push(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    match Self::parse_array(buffer, pos) {
+      Step::Done(Value::Array(Some(items))) => Step::Done(Value::Push(items)),
+      Step::Done(Value::Array(None)) => {
+        Step::Invalid("ERR Protocol error: invalid push length".to_string())
+      }
+      Step::Done(_) => unreachable!(),
+      Step::Incomplete => Step::Incomplete,
+      Step::Invalid(msg) => Step::Invalid(msg),
+    }
+  }
+
+  /// Parse a legacy inline command: a plain line, split on whitespace,
+  /// honoring single- and double-quoted segments (e.g. `SET k "hello world"`).
+  fn parse_inline(buffer: &[u8], pos: &mut usize) -> Step<Value> {
+    let line = try_step!(Self::read_line(buffer, pos));
+
+    let tokens = match Self::split_inline_tokens(line) {
+      Some(tokens) => tokens,
+      None => {
+        return Step::Invalid("ERR Protocol error: unbalanced quotes in request".to_string());
+      }
+    };
+
+    Step::Done(Value::Array(Some(
+      tokens
+        .into_iter()
+        .map(|t| Value::BulkString(Some(t)))
+        .collect(),
+    )))
+  }
+
+  /// Split an inline command line on whitespace, treating `"..."` and `'...'`
+  /// runs as single tokens. Returns `None` on unbalanced quotes.
+  fn split_inline_tokens(line: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut tokens = Vec::new();
+    let mut current = Vec::new();
+    let mut in_token = false;
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+
+    while i < line.len() {
+      let b = line[i];
+      match quote {
+        Some(q) if b == q => {
+          quote = None;
+          in_token = true;
+        }
+        Some(_) => current.push(b),
+        None => {
+          if b == b'"' || b == b'\'' {
+            quote = Some(b);
+            in_token = true;
+          } else if b.is_ascii_whitespace() {
+            if in_token {
+              tokens.push(std::mem::take(&mut current));
+              in_token = false;
+            }
+          } else {
+            current.push(b);
+            in_token = true;
+          }
+        }
+      }
+      i += 1;
+    }
+
+    if quote.is_some() {
+      return None; // unterminated quote
+    }
+
+    if in_token {
+      tokens.push(current);
+    }
+
+    Some(tokens)
+  }
+
+  /// Read one `\r\n`- or bare `\n`-terminated line, returning the bytes
+  /// before the terminator. Stops short of splitting a multi-byte UTF-8
+  /// sequence or bulk payload -- callers only see complete lines.
+  fn read_line<'a>(buffer: &'a [u8], pos: &mut usize) -> Step<&'a [u8]> {
     let start = *pos;
 
-    // Find \r\n
-    for i in start..buffer.len().saturating_sub(1) {
-      if buffer[i] == b'\r' && buffer[i + 1] == b'\n' {
-        *pos = i + 2;
-        return Some(&buffer[start..i]);
+    for i in start..buffer.len() {
+      if buffer[i] == b'\n' {
+        let end = if i > start && buffer[i - 1] == b'\r' {
+          i - 1
+        } else {
+          i
+        };
+        *pos = i + 1;
+        return Step::Done(&buffer[start..end]);
       }
     }
 
-    None
+    Step::Incomplete
   }
 }
 
@@ -235,4 +615,347 @@ mod tests {
     let value = Value::BulkString(Some(b"hello".to_vec()));
     assert_eq!(value.encode(), b"$5\r\nhello\r\n");
   }
+
+  #[test]
+  fn test_incomplete_simple_string() {
+    assert_eq!(Parser::parse_frame(b"+OK\r"), ParseResult::Incomplete);
+    assert_eq!(Parser::parse_frame(b"+OK"), ParseResult::Incomplete);
+    assert_eq!(Parser::parse_frame(b""), ParseResult::Incomplete);
+  }
+
+  #[test]
+  fn test_incomplete_bulk_string_length_prefix() {
+    assert_eq!(Parser::parse_frame(b"$5\r\nhel"), ParseResult::Incomplete);
+  }
+
+  #[test]
+  fn test_invalid_bulk_string_length() {
+    match Parser::parse_frame(b"$abc\r\n") {
+      ParseResult::Invalid(_) => {}
+      other => panic!("expected Invalid, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_invalid_multibulk_length() {
+    match Parser::parse_frame(b"*abc\r\n") {
+      ParseResult::Invalid(_) => {}
+      other => panic!("expected Invalid, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_feed_one_byte_at_a_time() {
+    let full = b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n";
+    let mut buf = Vec::new();
+    let mut result = None;
+
+    for &byte in full.iter() {
+      buf.push(byte);
+      match Parser::parse_frame(&buf) {
+        ParseResult::Complete(value, consumed) => {
+          result = Some((value, consumed));
+          break;
+        }
+        ParseResult::Incomplete => continue,
+        ParseResult::Invalid(msg) => panic!("unexpected invalid frame: {}", msg),
+      }
+    }
+
+    let (value, consumed) = result.expect("frame should complete once all bytes arrive");
+    assert_eq!(consumed, full.len());
+    match value {
+      Value::Array(Some(items)) => assert_eq!(items.len(), 3),
+      _ => panic!("expected array"),
+    }
+  }
+
+  #[test]
+  fn test_split_across_arbitrary_points() {
+    let full = b"*2\r\n$3\r\nGET\r\n$3\r\nfoo\r\n".to_vec();
+
+    for split in 0..=full.len() {
+      let (first, second) = full.split_at(split);
+      let mut buf = first.to_vec();
+
+      let result = match Parser::parse_frame(&buf) {
+        ParseResult::Complete(value, consumed) => Some((value, consumed)),
+        ParseResult::Incomplete => {
+          buf.extend_from_slice(second);
+          match Parser::parse_frame(&buf) {
+            ParseResult::Complete(value, consumed) => Some((value, consumed)),
+            other => panic!("expected Complete after feeding remaining bytes, got {:?}", other),
+          }
+        }
+        ParseResult::Invalid(msg) => panic!("unexpected invalid frame at split {}: {}", split, msg),
+      };
+
+      let (value, consumed) = result.unwrap();
+      assert_eq!(consumed, full.len());
+      match value {
+        Value::Array(Some(items)) => assert_eq!(items.len(), 2),
+        _ => panic!("expected array"),
+      }
+    }
+  }
+
+  #[test]
+  fn test_split_mid_utf8_sequence() {
+    // A bulk string whose payload is a multi-byte UTF-8 character ("é" = 0xC3 0xA9).
+    let full = b"$2\r\n\xc3\xa9\r\n";
+    // Split right in the middle of the two-byte payload.
+    let (first, second) = full.split_at(5);
+    assert_eq!(Parser::parse_frame(first), ParseResult::Incomplete);
+
+    let mut buf = first.to_vec();
+    buf.extend_from_slice(second);
+    match Parser::parse_frame(&buf) {
+      ParseResult::Complete(Value::BulkString(Some(data)), consumed) => {
+        assert_eq!(data, vec![0xc3, 0xa9]);
+        assert_eq!(consumed, full.len());
+      }
+      other => panic!("expected Complete bulk string, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_inline_command_unquoted() {
+    let data = b"SET foo bar\r\n";
+    match Parser::parse_frame(data) {
+      ParseResult::Complete(Value::Array(Some(items)), consumed) => {
+        assert_eq!(consumed, data.len());
+        assert_eq!(
+          items,
+          vec![
+            Value::BulkString(Some(b"SET".to_vec())),
+            Value::BulkString(Some(b"foo".to_vec())),
+            Value::BulkString(Some(b"bar".to_vec())),
+          ]
+        );
+      }
+      other => panic!("expected Complete array, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_inline_command_quoted() {
+    let data = b"SET k \"hello world\"\r\n";
+    match Parser::parse_frame(data) {
+      ParseResult::Complete(Value::Array(Some(items)), _) => {
+        assert_eq!(
+          items,
+          vec![
+            Value::BulkString(Some(b"SET".to_vec())),
+            Value::BulkString(Some(b"k".to_vec())),
+            Value::BulkString(Some(b"hello world".to_vec())),
+          ]
+        );
+      }
+      other => panic!("expected Complete array, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_inline_command_empty_line() {
+    let data = b"\r\n";
+    match Parser::parse_frame(data) {
+      ParseResult::Complete(Value::Array(Some(items)), consumed) => {
+        assert!(items.is_empty());
+        assert_eq!(consumed, data.len());
+      }
+      other => panic!("expected Complete empty array, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_inline_command_incomplete() {
+    let data = b"SET foo bar";
+    assert_eq!(Parser::parse_frame(data), ParseResult::Incomplete);
+  }
+
+  #[test]
+  fn test_parse_inline_command_via_option_api() {
+    // `Parser::parse` (the `Option<(Value, usize)>` contract used by early
+    // callers) should accept inline commands just like `parse_frame`.
+    let (value, consumed) = Parser::parse(b"GET mykey\r\n").unwrap();
+    assert_eq!(
+      value,
+      Value::Array(Some(vec![
+        Value::BulkString(Some(b"GET".to_vec())),
+        Value::BulkString(Some(b"mykey".to_vec())),
+      ]))
+    );
+    assert_eq!(consumed, b"GET mykey\r\n".len());
+  }
+
+  #[test]
+  fn test_parse_inline_command_incomplete_via_option_api() {
+    assert_eq!(Parser::parse(b"GET mykey"), None);
+  }
+
+  #[test]
+  fn test_null_round_trip() {
+    assert_eq!(Value::Null.encode_resp(3), b"_\r\n");
+    match Parser::parse_frame(b"_\r\n") {
+      ParseResult::Complete(Value::Null, consumed) => assert_eq!(consumed, 3),
+      other => panic!("expected Complete Null, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_null_downgrades_to_resp2_nil() {
+    assert_eq!(Value::Null.encode_resp(2), b"$-1\r\n");
+    assert_eq!(Value::Null.encode(), b"$-1\r\n");
+  }
+
+  #[test]
+  fn test_double_round_trip() {
+    assert_eq!(Value::Double(3.14).encode_resp(3), b",3.14\r\n");
+    match Parser::parse_frame(b",3.14\r\n") {
+      ParseResult::Complete(Value::Double(d), _) => assert_eq!(d, 3.14),
+      other => panic!("expected Complete Double, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_double_downgrades_to_bulk_string() {
+    assert_eq!(Value::Double(1.5).encode_resp(2), b"$3\r\n1.5\r\n");
+  }
+
+  #[test]
+  fn test_boolean_round_trip() {
+    assert_eq!(Value::Boolean(true).encode_resp(3), b"#t\r\n");
+    assert_eq!(Value::Boolean(false).encode_resp(3), b"#f\r\n");
+    assert_eq!(Parser::parse_frame(b"#t\r\n"), ParseResult::Complete(Value::Boolean(true), 4));
+    assert_eq!(Parser::parse_frame(b"#f\r\n"), ParseResult::Complete(Value::Boolean(false), 4));
+  }
+
+  #[test]
+  fn test_boolean_downgrades_to_integer() {
+    assert_eq!(Value::Boolean(true).encode_resp(2), b":1\r\n");
+    assert_eq!(Value::Boolean(false).encode_resp(2), b":0\r\n");
+  }
+
+  #[test]
+  fn test_big_number_round_trip() {
+    let big = "3492890328409238509324850943850943825024385".to_string();
+    let value = Value::BigNumber(big.clone());
+    let encoded = value.encode_resp(3);
+    assert_eq!(encoded, format!("({}\r\n", big).into_bytes());
+    match Parser::parse_frame(&encoded) {
+      ParseResult::Complete(Value::BigNumber(s), consumed) => {
+        assert_eq!(s, big);
+        assert_eq!(consumed, encoded.len());
+      }
+      other => panic!("expected Complete BigNumber, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_bulk_error_round_trip() {
+    let value = Value::BulkError("SYNTAX invalid syntax".to_string());
+    let encoded = value.encode_resp(3);
+    assert_eq!(encoded, b"!21\r\nSYNTAX invalid syntax\r\n");
+    match Parser::parse_frame(&encoded) {
+      ParseResult::Complete(Value::BulkError(msg), consumed) => {
+        assert_eq!(msg, "SYNTAX invalid syntax");
+        assert_eq!(consumed, encoded.len());
+      }
+      other => panic!("expected Complete BulkError, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_bulk_error_downgrades_to_error() {
+    assert_eq!(Value::BulkError("oops".to_string()).encode_resp(2), b"-oops\r\n");
+  }
+
+  #[test]
+  fn test_verbatim_string_round_trip() {
+    let value = Value::VerbatimString("txt".to_string(), b"Some string".to_vec());
+    let encoded = value.encode_resp(3);
+    assert_eq!(encoded, b"=15\r\ntxt:Some string\r\n");
+    match Parser::parse_frame(&encoded) {
+      ParseResult::Complete(Value::VerbatimString(format, data), consumed) => {
+        assert_eq!(format, "txt");
+        assert_eq!(data, b"Some string".to_vec());
+        assert_eq!(consumed, encoded.len());
+      }
+      other => panic!("expected Complete VerbatimString, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_verbatim_string_downgrades_to_bulk_string() {
+    let value = Value::VerbatimString("txt".to_string(), b"hi".to_vec());
+    assert_eq!(value.encode_resp(2), b"$2\r\nhi\r\n");
+  }
+
+  #[test]
+  fn test_map_round_trip() {
+    let value = Value::Map(vec![(
+      Value::BulkString(Some(b"key".to_vec())),
+      Value::Integer(42),
+    )]);
+    let encoded = value.encode_resp(3);
+    assert_eq!(encoded, b"%1\r\n$3\r\nkey\r\n:42\r\n");
+    match Parser::parse_frame(&encoded) {
+      ParseResult::Complete(Value::Map(pairs), consumed) => {
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].0, Value::BulkString(Some(b"key".to_vec())));
+        assert_eq!(pairs[0].1, Value::Integer(42));
+        assert_eq!(consumed, encoded.len());
+      }
+      other => panic!("expected Complete Map, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_map_downgrades_to_flat_array() {
+    let value = Value::Map(vec![(
+      Value::BulkString(Some(b"key".to_vec())),
+      Value::Integer(42),
+    )]);
+    assert_eq!(value.encode_resp(2), b"*2\r\n$3\r\nkey\r\n:42\r\n");
+  }
+
+  #[test]
+  fn test_set_round_trip() {
+    let value = Value::Set(vec![Value::Integer(1), Value::Integer(2)]);
+    let encoded = value.encode_resp(3);
+    assert_eq!(encoded, b"~2\r\n:1\r\n:2\r\n");
+    match Parser::parse_frame(&encoded) {
+      ParseResult::Complete(Value::Set(items), consumed) => {
+        assert_eq!(items, vec![Value::Integer(1), Value::Integer(2)]);
+        assert_eq!(consumed, encoded.len());
+      }
+      other => panic!("expected Complete Set, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_set_downgrades_to_array() {
+    let value = Value::Set(vec![Value::Integer(1)]);
+    assert_eq!(value.encode_resp(2), b"*1\r\n:1\r\n");
+  }
+
+  #[test]
+  fn test_push_round_trip() {
+    let value = Value::Push(vec![Value::BulkString(Some(b"message".to_vec()))]);
+    let encoded = value.encode_resp(3);
+    assert_eq!(encoded, b">1\r\n$7\r\nmessage\r\n");
+    match Parser::parse_frame(&encoded) {
+      ParseResult::Complete(Value::Push(items), consumed) => {
+        assert_eq!(items, vec![Value::BulkString(Some(b"message".to_vec()))]);
+        assert_eq!(consumed, encoded.len());
+      }
+      other => panic!("expected Complete Push, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_push_downgrades_to_array() {
+    let value = Value::Push(vec![Value::Integer(7)]);
+    assert_eq!(value.encode_resp(2), b"*1\r\n:7\r\n");
+  }
 }
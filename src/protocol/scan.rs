@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+
+use crate::protocol::command::Command;
+use crate::protocol::resp::Value;
+use crate::store::Store;
+
+/// SCAN cursor [MATCH pattern] [COUNT n] command executor: a non-blocking,
+/// cursor-based walk over the keyspace.
+pub struct ScanCmd;
+
+#[async_trait]
+impl Command for ScanCmd {
+    async fn execute(&self, items: &[Value], store: &Store) -> Value {
+        if items.len() < 2 {
+            return Value::error("ERR wrong number of arguments for 'scan' command");
+        }
+
+        let cursor = match parse_u64(&items[1]) {
+            Some(cursor) => cursor,
+            None => return Value::error("ERR invalid cursor"),
+        };
+
+        let mut pattern: Option<String> = None;
+        let mut count: usize = 10;
+
+        let mut i = 2;
+        while i < items.len() {
+            let option = match &items[i] {
+                Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_uppercase(),
+                Value::SimpleString(s) => s.to_uppercase(),
+                _ => return Value::error("ERR syntax error"),
+            };
+
+            match option.as_str() {
+                "MATCH" => {
+                    if i + 1 >= items.len() {
+                        return Value::error("ERR syntax error");
+                    }
+                    pattern = match &items[i + 1] {
+                        Value::BulkString(Some(data)) => {
+                            Some(String::from_utf8_lossy(data).to_string())
+                        }
+                        Value::SimpleString(s) => Some(s.clone()),
+                        _ => return Value::error("ERR syntax error"),
+                    };
+                    i += 2;
+                }
+                "COUNT" => {
+                    if i + 1 >= items.len() {
+                        return Value::error("ERR syntax error");
+                    }
+                    count = match parse_u64(&items[i + 1]) {
+                        Some(n) if n > 0 => n as usize,
+                        _ => return Value::error("ERR value is not an integer or out of range"),
+                    };
+                    i += 2;
+                }
+                _ => return Value::error("ERR syntax error"),
+            }
+        }
+
+        match store.scan(cursor, pattern.as_deref(), count) {
+            Ok((next_cursor, keys)) => Value::Array(Some(vec![
+                Value::BulkString(Some(next_cursor.to_string().into_bytes())),
+                Value::Array(Some(
+                    keys.into_iter()
+                        .map(|k| Value::BulkString(Some(k.into_bytes())))
+                        .collect(),
+                )),
+            ])),
+            Err(e) => Value::error(format!("ERR {}", e)),
+        }
+    }
+}
+
+fn parse_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::BulkString(Some(data)) => String::from_utf8_lossy(data).parse::<u64>().ok(),
+        Value::SimpleString(s) => s.parse::<u64>().ok(),
+        Value::Integer(i) if *i >= 0 => Some(*i as u64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scan_reaches_completion() {
+        let store = Store::new();
+        for i in 0..5 {
+            store
+                .set(format!("k{}", i), b"v".to_vec())
+                .unwrap();
+        }
+
+        let mut cursor = Value::Integer(0);
+        let mut seen = std::collections::HashSet::new();
+        loop {
+            let items = vec![
+                Value::BulkString(Some(b"SCAN".to_vec())),
+                cursor.clone(),
+            ];
+            let result = ScanCmd.execute(&items, &store).await;
+            match result {
+                Value::Array(Some(parts)) => {
+                    let next = match &parts[0] {
+                        Value::BulkString(Some(data)) => {
+                            String::from_utf8_lossy(data).to_string()
+                        }
+                        _ => panic!("expected bulk string cursor"),
+                    };
+                    if let Value::Array(Some(keys)) = &parts[1] {
+                        for k in keys {
+                            if let Value::BulkString(Some(data)) = k {
+                                seen.insert(String::from_utf8_lossy(data).to_string());
+                            }
+                        }
+                    }
+                    cursor = Value::BulkString(Some(next.clone().into_bytes()));
+                    if next == "0" {
+                        break;
+                    }
+                }
+                other => panic!("expected array reply, got {:?}", other),
+            }
+        }
+
+        assert_eq!(seen.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_scan_wrong_args() {
+        let store = Store::new();
+        let items = vec![Value::BulkString(Some(b"SCAN".to_vec()))];
+        let result = ScanCmd.execute(&items, &store).await;
+        assert_eq!(
+            result,
+            Value::error("ERR wrong number of arguments for 'scan' command")
+        );
+    }
+}
@@ -1,7 +1,6 @@
-use crate::encoding::StringValue;
 use crate::protocol::command::Command;
 use crate::protocol::resp::Value;
-use crate::server::Server;
+use crate::store::Store;
 use crate::util::now_ms;
 use async_trait::async_trait;
 
@@ -166,46 +165,48 @@ fn parse_u64(value: &Value) -> Option<u64> {
   }
 }
 
-/// SET command executor
-pub struct SetCommand;
+/// SET command executor operating directly on a `Store`, used by the plain
+/// (non-Raft) TCP server's `CommandFactory`. Implements `NX`/`XX`/`GET`/
+/// `KEEPTTL` as a single atomic read-modify-write via `Store::set_conditional`.
+pub struct SetCmd;
 
 #[async_trait]
-impl Command for SetCommand {
-  async fn execute(&self, items: &[Value], server: &Server) -> Value {
+impl Command for SetCmd {
+  async fn execute(&self, items: &[Value], store: &Store) -> Value {
     let params = match SetParams::parse(items) {
       Some(params) => params,
       None => return Value::error("ERR wrong number of arguments for 'set' command"),
     };
 
-    // Calculate expiration timestamp in milliseconds
-    let expires_at = params.expiration.and_then(|exp| {
+    let keep_ttl = params.expiration == Some(Expiration::KeepTtl);
+    let expires_at = params.expiration.as_ref().and_then(|exp| {
       let now = now_ms();
       match exp {
         Expiration::Ex(seconds) => Some(now + seconds * 1000),
         Expiration::Px(millis) => Some(now + millis),
         Expiration::ExAt(timestamp) => Some(timestamp * 1000),
-        Expiration::PxAt(timestamp) => Some(timestamp),
-        Expiration::KeepTtl => None, // TODO: Implement KEEPTTL
+        Expiration::PxAt(timestamp) => Some(*timestamp),
+        Expiration::KeepTtl => None,
       }
     });
 
-    // Create StringValue and serialize
-    let string_value = match expires_at {
-      Some(exp) => StringValue::with_expiration(params.value, exp),
-      None => StringValue::new(params.value),
+    let (nx, xx) = match params.mode {
+      Some(SetMode::Nx) => (true, false),
+      Some(SetMode::Xx) => (false, true),
+      None => (false, false),
     };
-    let serialized = string_value.serialize();
 
-    // TODO: Implement NX/XX mode logic
-    // TODO: Implement GET option to return previous value
-    // TODO: Implement KEEPTTL for expiration
-
-    // For now, just set the value (basic implementation)
-    match server.set(params.key, serialized).await {
-      Ok(_) => {
+    match store.set_conditional(params.key, params.value, nx, xx, keep_ttl, expires_at) {
+      Ok(result) => {
+        if !result.applied {
+          return if params.get {
+            Value::BulkString(result.previous)
+          } else {
+            Value::BulkString(None)
+          };
+        }
         if params.get {
-          // TODO: Return previous value when GET option is implemented
-          Value::BulkString(None)
+          Value::BulkString(result.previous)
         } else {
           Value::ok()
         }
@@ -218,86 +219,6 @@ impl Command for SetCommand {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::server::Server;
-  use std::sync::Arc;
-
-  #[tokio::test]
-  async fn test_set_and_get_with_expiration() {
-    let server = Arc::new(Server::bind("127.0.0.1:0").await.unwrap());
-
-    // Set key with 1 second expiration
-    let set_items = vec![
-      Value::BulkString(Some(b"SET".to_vec())),
-      Value::BulkString(Some(b"test_key".to_vec())),
-      Value::BulkString(Some(b"test_value".to_vec())),
-      Value::BulkString(Some(b"PX".to_vec())),
-      Value::BulkString(Some(b"100".to_vec())), // 100ms expiration
-    ];
-
-    let set_cmd = SetCommand;
-    let result = set_cmd.execute(&set_items, &server).await;
-    assert_eq!(result, Value::ok());
-
-    // Get should return the value immediately
-    let get_items = vec![
-      Value::BulkString(Some(b"GET".to_vec())),
-      Value::BulkString(Some(b"test_key".to_vec())),
-    ];
-
-    let get_cmd = crate::protocol::get::GetCommand;
-    let result = get_cmd.execute(&get_items, &server).await;
-    assert_eq!(result, Value::BulkString(Some(b"test_value".to_vec())));
-
-    // Wait for expiration
-    tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
-
-    // Get should return null after expiration
-    let result = get_cmd.execute(&get_items, &server).await;
-    assert_eq!(result, Value::BulkString(None));
-  }
-
-  #[tokio::test]
-  async fn test_set_without_expiration() {
-    let server = Arc::new(Server::bind("127.0.0.1:0").await.unwrap());
-
-    // Set key without expiration
-    let set_items = vec![
-      Value::BulkString(Some(b"SET".to_vec())),
-      Value::BulkString(Some(b"persistent_key".to_vec())),
-      Value::BulkString(Some(b"persistent_value".to_vec())),
-    ];
-
-    let set_cmd = SetCommand;
-    let result = set_cmd.execute(&set_items, &server).await;
-    assert_eq!(result, Value::ok());
-
-    // Get should return the value
-    let get_items = vec![
-      Value::BulkString(Some(b"GET".to_vec())),
-      Value::BulkString(Some(b"persistent_key".to_vec())),
-    ];
-
-    let get_cmd = crate::protocol::get::GetCommand;
-    let result = get_cmd.execute(&get_items, &server).await;
-    assert_eq!(
-      result,
-      Value::BulkString(Some(b"persistent_value".to_vec()))
-    );
-  }
-
-  #[tokio::test]
-  async fn test_get_nonexistent_key() {
-    let server = Arc::new(Server::bind("127.0.0.1:0").await.unwrap());
-
-    let get_items = vec![
-      Value::BulkString(Some(b"GET".to_vec())),
-      Value::BulkString(Some(b"nonexistent_key".to_vec())),
-    ];
-
-    let get_cmd = crate::protocol::get::GetCommand;
-    let result = get_cmd.execute(&get_items, &server).await;
-    assert_eq!(result, Value::BulkString(None));
-  }
 
   #[test]
   fn test_set_params_parse_basic() {
@@ -462,4 +383,122 @@ mod tests {
     ];
     assert!(SetParams::parse(&items).is_none());
   }
+
+  fn set_items(args: &[&[u8]]) -> Vec<Value> {
+    args
+      .iter()
+      .map(|a| Value::BulkString(Some(a.to_vec())))
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_nx_skips_existing_key() {
+    let store = Store::new();
+    store.set("k".to_string(), b"first".to_vec()).unwrap();
+
+    let items = set_items(&[b"SET", b"k", b"second", b"NX"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::BulkString(None));
+    assert_eq!(store.get("k").unwrap(), Some(b"first".to_vec()));
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_nx_applies_when_absent() {
+    let store = Store::new();
+
+    let items = set_items(&[b"SET", b"k", b"value", b"NX"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::ok());
+    assert_eq!(store.get("k").unwrap(), Some(b"value".to_vec()));
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_xx_skips_absent_key() {
+    let store = Store::new();
+
+    let items = set_items(&[b"SET", b"k", b"value", b"XX"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::BulkString(None));
+    assert_eq!(store.get("k").unwrap(), None);
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_xx_applies_when_present() {
+    let store = Store::new();
+    store.set("k".to_string(), b"first".to_vec()).unwrap();
+
+    let items = set_items(&[b"SET", b"k", b"second", b"XX"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::ok());
+    assert_eq!(store.get("k").unwrap(), Some(b"second".to_vec()));
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_get_returns_previous_value() {
+    let store = Store::new();
+    store.set("k".to_string(), b"first".to_vec()).unwrap();
+
+    let items = set_items(&[b"SET", b"k", b"second", b"GET"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::BulkString(Some(b"first".to_vec())));
+    assert_eq!(store.get("k").unwrap(), Some(b"second".to_vec()));
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_get_returns_nil_when_absent() {
+    let store = Store::new();
+
+    let items = set_items(&[b"SET", b"k", b"value", b"GET"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::BulkString(None));
+    assert_eq!(store.get("k").unwrap(), Some(b"value".to_vec()));
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_nx_get_returns_previous_and_skips_write() {
+    let store = Store::new();
+    store.set("k".to_string(), b"first".to_vec()).unwrap();
+
+    let items = set_items(&[b"SET", b"k", b"second", b"NX", b"GET"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::BulkString(Some(b"first".to_vec())));
+    assert_eq!(store.get("k").unwrap(), Some(b"first".to_vec()));
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_keepttl_preserves_expiration() {
+    let store = Store::new();
+    let expires_at = now_ms() + 50;
+    store
+      .set_with_expiry("k".to_string(), b"first".to_vec(), Some(expires_at))
+      .unwrap();
+
+    let items = set_items(&[b"SET", b"k", b"second", b"KEEPTTL"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::ok());
+    assert_eq!(store.get("k").unwrap(), Some(b"second".to_vec()));
+
+    // The original TTL should still apply to the new value: once it elapses
+    // the key must expire, proving KEEPTTL carried it forward instead of
+    // dropping it.
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    assert_eq!(store.get("k").unwrap(), None);
+  }
+
+  #[tokio::test]
+  async fn test_set_cmd_without_keepttl_clears_expiration() {
+    let store = Store::new();
+    store
+      .set_with_expiry("k".to_string(), b"first".to_vec(), Some(now_ms() + 60_000))
+      .unwrap();
+
+    let items = set_items(&[b"SET", b"k", b"second"]);
+    let result = SetCmd.execute(&items, &store).await;
+    assert_eq!(result, Value::ok());
+
+    // No expiration was requested this time, so the previous TTL must not
+    // have carried over.
+    tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+    assert_eq!(store.get("k").unwrap(), Some(b"second".to_vec()));
+  }
 }
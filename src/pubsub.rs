@@ -0,0 +1,274 @@
+//! Publish/subscribe messaging, independent of the key-value `Store`.
+//!
+//! Connections register an `mpsc` sender under a channel name (`SUBSCRIBE`)
+//! or a glob pattern (`PSUBSCRIBE`); `PUBLISH` fans a message out to every
+//! matching sender and reports how many subscribers received it.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use tokio::sync::mpsc;
+
+use crate::protocol::resp::Value;
+
+/// Identifies a single subscribed connection.
+pub type SubscriberId = u64;
+
+/// A message pushed to subscribers of `channel`.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub channel: String,
+    pub payload: Vec<u8>,
+}
+
+impl Message {
+    /// Encode as the RESP push Redis clients expect: `["message", channel, payload]`.
+    pub fn encode(&self) -> Value {
+        Value::Array(Some(vec![
+            Value::BulkString(Some(b"message".to_vec())),
+            Value::BulkString(Some(self.channel.clone().into_bytes())),
+            Value::BulkString(Some(self.payload.clone())),
+        ]))
+    }
+}
+
+type SenderMap = HashMap<SubscriberId, mpsc::UnboundedSender<Value>>;
+
+struct Subscriptions {
+    /// Exact channel name -> subscribers.
+    channels: HashMap<String, SenderMap>,
+    /// Glob pattern -> subscribers (PSUBSCRIBE).
+    patterns: HashMap<String, SenderMap>,
+}
+
+/// Channel-based publish/subscribe registry.
+pub struct PubSub {
+    subs: RwLock<Subscriptions>,
+    next_id: AtomicU64,
+}
+
+impl PubSub {
+    pub fn new() -> Self {
+        Self {
+            subs: RwLock::new(Subscriptions {
+                channels: HashMap::new(),
+                patterns: HashMap::new(),
+            }),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Allocate a fresh id for a newly-connecting subscriber.
+    pub fn new_subscriber_id(&self) -> SubscriberId {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Subscribe `id` to an exact channel name.
+    pub fn subscribe(&self, channel: &str, id: SubscriberId, sender: mpsc::UnboundedSender<Value>) {
+        let mut subs = self.subs.write().expect("pubsub lock poisoned");
+        subs.channels
+            .entry(channel.to_string())
+            .or_default()
+            .insert(id, sender);
+    }
+
+    /// Unsubscribe `id` from an exact channel name.
+    pub fn unsubscribe(&self, channel: &str, id: SubscriberId) {
+        let mut subs = self.subs.write().expect("pubsub lock poisoned");
+        if let Some(senders) = subs.channels.get_mut(channel) {
+            senders.remove(&id);
+            if senders.is_empty() {
+                subs.channels.remove(channel);
+            }
+        }
+    }
+
+    /// Subscribe `id` to a glob pattern (`*`, `?`, `[...]`).
+    pub fn psubscribe(&self, pattern: &str, id: SubscriberId, sender: mpsc::UnboundedSender<Value>) {
+        let mut subs = self.subs.write().expect("pubsub lock poisoned");
+        subs.patterns
+            .entry(pattern.to_string())
+            .or_default()
+            .insert(id, sender);
+    }
+
+    /// Unsubscribe `id` from a glob pattern.
+    pub fn punsubscribe(&self, pattern: &str, id: SubscriberId) {
+        let mut subs = self.subs.write().expect("pubsub lock poisoned");
+        if let Some(senders) = subs.patterns.get_mut(pattern) {
+            senders.remove(&id);
+            if senders.is_empty() {
+                subs.patterns.remove(pattern);
+            }
+        }
+    }
+
+    /// Drop every subscription held by `id`, e.g. when its connection closes.
+    pub fn unsubscribe_all(&self, id: SubscriberId) {
+        let mut subs = self.subs.write().expect("pubsub lock poisoned");
+        subs.channels.retain(|_, senders| {
+            senders.remove(&id);
+            !senders.is_empty()
+        });
+        subs.patterns.retain(|_, senders| {
+            senders.remove(&id);
+            !senders.is_empty()
+        });
+    }
+
+    /// Publish `payload` to `channel`, returning the number of subscribers
+    /// (exact-channel and pattern matches) it was delivered to.
+    pub fn publish(&self, channel: &str, payload: Vec<u8>) -> usize {
+        let message = Message {
+            channel: channel.to_string(),
+            payload,
+        };
+        let encoded = message.encode();
+
+        let subs = self.subs.read().expect("pubsub lock poisoned");
+        let mut delivered = 0;
+
+        if let Some(senders) = subs.channels.get(channel) {
+            for sender in senders.values() {
+                if sender.send(encoded.clone()).is_ok() {
+                    delivered += 1;
+                }
+            }
+        }
+
+        for (pattern, senders) in subs.patterns.iter() {
+            if glob_match(pattern, channel) {
+                for sender in senders.values() {
+                    if sender.send(encoded.clone()).is_ok() {
+                        delivered += 1;
+                    }
+                }
+            }
+        }
+
+        delivered
+    }
+}
+
+impl Default for PubSub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Match `text` against a glob `pattern` supporting `*`, `?`, and `[...]`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_inner(&pattern, &text)
+}
+
+fn glob_match_inner(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => {
+            glob_match_inner(&pattern[1..], text)
+                || (!text.is_empty() && glob_match_inner(pattern, &text[1..]))
+        }
+        Some('?') => !text.is_empty() && glob_match_inner(&pattern[1..], &text[1..]),
+        Some('[') => {
+            let Some(close) = pattern.iter().position(|&c| c == ']') else {
+                // Unterminated class: treat '[' as a literal.
+                return !text.is_empty() && text[0] == '[' && glob_match_inner(&pattern[1..], &text[1..]);
+            };
+            if text.is_empty() {
+                return false;
+            }
+            let class = &pattern[1..close];
+            let (negate, class) = match class.first() {
+                Some('^') => (true, &class[1..]),
+                _ => (false, class),
+            };
+            let matched = class_matches(class, text[0]);
+            if matched != negate {
+                glob_match_inner(&pattern[close + 1..], &text[1..])
+            } else {
+                false
+            }
+        }
+        Some(&c) => !text.is_empty() && text[0] == c && glob_match_inner(&pattern[1..], &text[1..]),
+    }
+}
+
+fn class_matches(class: &[char], c: char) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            if class[i] <= c && c <= class[i + 2] {
+                return true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match_star() {
+        assert!(glob_match("news.*", "news.tech"));
+        assert!(glob_match("*", "anything"));
+        assert!(!glob_match("news.*", "sports.tech"));
+    }
+
+    #[test]
+    fn test_glob_match_question_mark() {
+        assert!(glob_match("h?llo", "hello"));
+        assert!(!glob_match("h?llo", "hllo"));
+    }
+
+    #[test]
+    fn test_glob_match_class() {
+        assert!(glob_match("h[ae]llo", "hello"));
+        assert!(glob_match("h[ae]llo", "hallo"));
+        assert!(!glob_match("h[ae]llo", "hillo"));
+        assert!(glob_match("[a-c]at", "bat"));
+        assert!(!glob_match("[a-c]at", "dat"));
+        assert!(glob_match("[^a-c]at", "dat"));
+    }
+
+    #[test]
+    fn test_publish_delivers_to_exact_and_pattern_subscribers() {
+        let pubsub = PubSub::new();
+        let (tx1, mut rx1) = mpsc::unbounded_channel();
+        let (tx2, mut rx2) = mpsc::unbounded_channel();
+
+        let id1 = pubsub.new_subscriber_id();
+        let id2 = pubsub.new_subscriber_id();
+        pubsub.subscribe("news.tech", id1, tx1);
+        pubsub.psubscribe("news.*", id2, tx2);
+
+        let delivered = pubsub.publish("news.tech", b"hello".to_vec());
+        assert_eq!(delivered, 2);
+
+        assert!(rx1.try_recv().is_ok());
+        assert!(rx2.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_unsubscribe_stops_delivery() {
+        let pubsub = PubSub::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let id = pubsub.new_subscriber_id();
+        pubsub.subscribe("ch", id, tx);
+        pubsub.unsubscribe("ch", id);
+
+        let delivered = pubsub.publish("ch", b"x".to_vec());
+        assert_eq!(delivered, 0);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -1,15 +1,78 @@
+/// Raft-backed `Server`, reachable as `crate::server::server::Server`.
+/// Unlike the plain TCP server below, it replicates writes through
+/// `rockraft` instead of keeping state in a local `Store`.
+pub mod server;
+
 use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig as RustlsServerConfig;
+use tokio_rustls::TlsAcceptor;
 use tracing::{error, info, warn};
 
+/// How long `run_until` waits for in-flight connections to drain on their
+/// own. Connections still active after this are not aborted: `run_until`
+/// logs how many remain and returns.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+use crate::persistence::Persistence;
+use crate::protocol::resp::ParseResult;
 use crate::protocol::{CommandFactory, Parser, Value};
 use crate::store::Store;
 
+/// Command names that mutate per-connection subscription state and so are
+/// handled directly in `handle_connection` instead of through the stateless
+/// `CommandFactory` dispatch.
+const PUBSUB_SUBSCRIBE_COMMANDS: &[&str] = &["SUBSCRIBE", "UNSUBSCRIBE", "PSUBSCRIBE", "PUNSUBSCRIBE"];
+
+/// RESP protocol version a connection starts in, until it negotiates RESP3
+/// via `HELLO 3`.
+const DEFAULT_PROTOCOL_VERSION: u8 = 2;
+
 /// Default listening port (Redis default port)
 const DEFAULT_PORT: u16 = 6379;
 
+/// TLS settings for a `Server`: a certificate chain and private key used to
+/// build a `tokio_rustls::TlsAcceptor` for incoming connections.
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+impl TlsConfig {
+    /// Load the cert chain and private key from disk and build a `TlsAcceptor`.
+    pub fn build_acceptor(&self) -> std::io::Result<TlsAcceptor> {
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_private_key(&self.key_path)?;
+
+        let server_config = RustlsServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+        Ok(TlsAcceptor::from(Arc::new(server_config)))
+    }
+}
+
+fn load_certs(path: impl AsRef<Path>) -> std::io::Result<Vec<CertificateDer<'static>>> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()
+}
+
+fn load_private_key(path: impl AsRef<Path>) -> std::io::Result<PrivateKeyDer<'static>> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "no private key found"))
+}
+
 /// TCP server handle for processing connections
 #[derive(Clone)]
 pub struct ServerHandle {
@@ -31,67 +94,254 @@ impl ServerHandle {
         self.cmd_factory.execute(value, &self.store).await
     }
 
-    /// Handle a single client connection
-    async fn handle_connection(
+    /// The underlying store, exposed so `Server::bind_with_data_dir` can
+    /// attach a persistence subsystem to it after construction.
+    pub(crate) fn store(&self) -> &Arc<Store> {
+        &self.store
+    }
+
+    /// Handle a single client connection over any byte stream (plaintext TCP
+    /// or a negotiated TLS stream).
+    ///
+    /// Multiplexes between reading client commands and draining this
+    /// connection's pub/sub receiver, so pushed messages can be written out
+    /// even while the client isn't issuing a new command.
+    async fn handle_connection<S>(
         &self,
-        mut stream: TcpStream,
+        mut stream: S,
         peer_addr: SocketAddr,
-    ) -> std::io::Result<()> {
+        mut shutdown: watch::Receiver<bool>,
+    ) -> std::io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
         let mut buffer = vec![0u8; 8192]; // 8KB buffer
         let mut pending = Vec::new(); // Buffer for incomplete commands
 
+        let subscriber_id = self.store.pubsub().new_subscriber_id();
+        let (push_tx, mut push_rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+        // RESP protocol version negotiated for this connection via `HELLO`.
+        let mut protocol = DEFAULT_PROTOCOL_VERSION;
+
         loop {
-            match stream.read(&mut buffer).await {
-                Ok(0) => {
-                    info!("Connection closed by client: {}", peer_addr);
-                    break;
+            tokio::select! {
+                biased;
+
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Shutting down connection to {} at the next frame boundary", peer_addr);
+                        break;
+                    }
                 }
-                Ok(n) => {
-                    // Append new data to pending buffer
-                    pending.extend_from_slice(&buffer[..n]);
-
-                    // Try to parse and process complete commands
-                    let mut processed = 0;
-                    loop {
-                        match Parser::parse(&pending[processed..]) {
-                            Some((value, consumed)) => {
-                                processed += consumed;
-
-                                // Log the parsed command
-                                info!("Received command from {}: {:?}", peer_addr, value);
-
-                                // Process the command and get response
-                                let response = self.process_command(value).await;
-                                let encoded = response.encode();
-
-                                // Send response
-                                if let Err(e) = stream.write_all(&encoded).await {
-                                    warn!("Failed to write response to {}: {}", peer_addr, e);
-                                    break;
-                                }
-                            }
-                            None => {
-                                // No complete command available
+
+                pushed = push_rx.recv() => {
+                    match pushed {
+                        Some(message) => {
+                            if let Err(e) = stream.write_all(&message.encode_resp(protocol)).await {
+                                warn!("Failed to write pub/sub push to {}: {}", peer_addr, e);
                                 break;
                             }
                         }
+                        None => continue, // sender side lives on in `push_tx`; never actually closes here
                     }
+                }
+
+                read_result = stream.read(&mut buffer) => {
+                    match read_result {
+                        Ok(0) => {
+                            info!("Connection closed by client: {}", peer_addr);
+                            break;
+                        }
+                        Ok(n) => {
+                            // Append new data to pending buffer
+                            pending.extend_from_slice(&buffer[..n]);
+
+                            // Try to parse and process complete commands
+                            let mut processed = 0;
+                            let mut protocol_error = false;
+                            loop {
+                                match Parser::parse_frame(&pending[processed..]) {
+                                    ParseResult::Complete(value, consumed) => {
+                                        processed += consumed;
+
+                                        // Log the parsed command
+                                        info!("Received command from {}: {:?}", peer_addr, value);
+
+                                        let responses = self
+                                            .dispatch(value, subscriber_id, &push_tx, &mut protocol)
+                                            .await;
 
-                    // Remove processed data from pending buffer
-                    if processed > 0 {
-                        pending = pending.split_off(processed);
+                                        for response in responses {
+                                            if let Err(e) =
+                                                stream.write_all(&response.encode_resp(protocol)).await
+                                            {
+                                                warn!("Failed to write response to {}: {}", peer_addr, e);
+                                                break;
+                                            }
+                                        }
+                                    }
+                                    ParseResult::Incomplete => {
+                                        // Frame not fully buffered yet; wait for more data.
+                                        break;
+                                    }
+                                    ParseResult::Invalid(msg) => {
+                                        warn!("Protocol error from {}: {}", peer_addr, msg);
+                                        let _ = stream.write_all(&Value::error(msg).encode()).await;
+                                        protocol_error = true;
+                                        break;
+                                    }
+                                }
+                            }
+
+                            // Remove processed data from pending buffer
+                            if processed > 0 {
+                                pending = pending.split_off(processed);
+                            }
+
+                            if protocol_error {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            error!("Error reading from {}: {}", peer_addr, e);
+                            break;
+                        }
                     }
                 }
-                Err(e) => {
-                    error!("Error reading from {}: {}", peer_addr, e);
-                    break;
-                }
             }
         }
 
+        self.store.pubsub().unsubscribe_all(subscriber_id);
         info!("Connection handler ended for {}", peer_addr);
         Ok(())
     }
+
+    /// Dispatch one parsed command, handling the pub/sub subscribe family and
+    /// `HELLO` directly (since they mutate this connection's local state) and
+    /// everything else through the stateless `CommandFactory`.
+    async fn dispatch(
+        &self,
+        value: Value,
+        subscriber_id: crate::pubsub::SubscriberId,
+        push_tx: &tokio::sync::mpsc::UnboundedSender<Value>,
+        protocol: &mut u8,
+    ) -> Vec<Value> {
+        let items = match &value {
+            Value::Array(Some(items)) if !items.is_empty() => items.clone(),
+            _ => return vec![self.process_command(value).await],
+        };
+
+        let name = match &items[0] {
+            Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_uppercase(),
+            Value::SimpleString(s) => s.to_uppercase(),
+            _ => return vec![self.process_command(value).await],
+        };
+
+        if name == "HELLO" {
+            return vec![self.hello(&items, subscriber_id, protocol)];
+        }
+
+        if !PUBSUB_SUBSCRIBE_COMMANDS.contains(&name.as_str()) {
+            return vec![self.process_command(value).await];
+        }
+
+        let pubsub = self.store.pubsub();
+        let targets: Vec<String> = items[1..]
+            .iter()
+            .filter_map(|item| match item {
+                Value::BulkString(Some(data)) => Some(String::from_utf8_lossy(data).to_string()),
+                Value::SimpleString(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+
+        targets
+            .into_iter()
+            .map(|target| {
+                match name.as_str() {
+                    "SUBSCRIBE" => pubsub.subscribe(&target, subscriber_id, push_tx.clone()),
+                    "UNSUBSCRIBE" => pubsub.unsubscribe(&target, subscriber_id),
+                    "PSUBSCRIBE" => pubsub.psubscribe(&target, subscriber_id, push_tx.clone()),
+                    "PUNSUBSCRIBE" => pubsub.punsubscribe(&target, subscriber_id),
+                    _ => unreachable!(),
+                }
+                Value::Array(Some(vec![
+                    Value::BulkString(Some(name.to_lowercase().into_bytes())),
+                    Value::BulkString(Some(target.into_bytes())),
+                    Value::Integer(1),
+                ]))
+            })
+            .collect()
+    }
+
+    /// Handle `HELLO [protover]`: negotiate the connection's RESP protocol
+    /// version and reply with a server-info map (encoded as a flat array on
+    /// RESP2 connections).
+    fn hello(
+        &self,
+        items: &[Value],
+        subscriber_id: crate::pubsub::SubscriberId,
+        protocol: &mut u8,
+    ) -> Value {
+        let requested = match items.len() {
+            1 => *protocol,
+            2 => match Self::parse_protover(&items[1]) {
+                Some(v) => v,
+                None => {
+                    return Value::error(
+                        "NOPROTO unsupported protocol version",
+                    )
+                }
+            },
+            _ => return Value::error("ERR syntax error in HELLO"),
+        };
+
+        *protocol = requested;
+
+        Value::Map(vec![
+            (
+                Value::BulkString(Some(b"server".to_vec())),
+                Value::BulkString(Some(b"coredb".to_vec())),
+            ),
+            (
+                Value::BulkString(Some(b"version".to_vec())),
+                Value::BulkString(Some(b"0.1.0".to_vec())),
+            ),
+            (
+                Value::BulkString(Some(b"proto".to_vec())),
+                Value::Integer(requested as i64),
+            ),
+            (
+                Value::BulkString(Some(b"id".to_vec())),
+                Value::Integer(subscriber_id as i64),
+            ),
+            (
+                Value::BulkString(Some(b"mode".to_vec())),
+                Value::BulkString(Some(b"standalone".to_vec())),
+            ),
+            (
+                Value::BulkString(Some(b"role".to_vec())),
+                Value::BulkString(Some(b"master".to_vec())),
+            ),
+            (
+                Value::BulkString(Some(b"modules".to_vec())),
+                Value::Array(Some(Vec::new())),
+            ),
+        ])
+    }
+
+    fn parse_protover(value: &Value) -> Option<u8> {
+        let text = match value {
+            Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_string(),
+            Value::SimpleString(s) => s.clone(),
+            _ => return None,
+        };
+        match text.as_str() {
+            "2" => Some(2),
+            "3" => Some(3),
+            _ => None,
+        }
+    }
 }
 
 /// TCP server
@@ -99,6 +349,11 @@ pub struct Server {
     listener: TcpListener,
     local_addr: SocketAddr,
     handle: ServerHandle,
+    /// TLS acceptor, present only when the server was created via `bind_tls`
+    tls_acceptor: Option<TlsAcceptor>,
+    /// Number of connections currently being served, used by `run_until` to
+    /// wait for a graceful drain on shutdown.
+    active_connections: Arc<AtomicUsize>,
 }
 
 impl Server {
@@ -110,12 +365,23 @@ impl Server {
 
         // Initialize command factory and register commands
         let mut cmd_factory = CommandFactory::new();
-        
-        // Register GET and SET commands
+
+        // Register GET, SET, PUBLISH, KEYS, DEL, SCAN and BGSAVE. SUBSCRIBE/UNSUBSCRIBE/
+        // PSUBSCRIBE/PUNSUBSCRIBE are handled directly in `handle_connection`
+        // since they mutate per-connection state (see `dispatch`).
+        use crate::protocol::bgsave::BgsaveCmd;
         use crate::protocol::get::GetCmd;
+        use crate::protocol::keys::{DelCmd, KeysCmd};
+        use crate::protocol::publish::PublishCmd;
+        use crate::protocol::scan::ScanCmd;
         use crate::protocol::set::SetCmd;
         cmd_factory.register("GET", GetCmd);
         cmd_factory.register("SET", SetCmd);
+        cmd_factory.register("PUBLISH", PublishCmd);
+        cmd_factory.register("KEYS", KeysCmd);
+        cmd_factory.register("DEL", DelCmd);
+        cmd_factory.register("SCAN", ScanCmd);
+        cmd_factory.register("BGSAVE", BgsaveCmd);
 
         Ok(Self {
             listener,
@@ -124,6 +390,8 @@ impl Server {
                 Arc::new(cmd_factory),
                 Arc::new(Store::new()),
             ),
+            tls_acceptor: None,
+            active_connections: Arc::new(AtomicUsize::new(0)),
         })
     }
 
@@ -132,34 +400,147 @@ impl Server {
         Self::bind(&format!("0.0.0.0:{}", DEFAULT_PORT)).await
     }
 
+    /// Create and bind a TCP server that terminates TLS on every accepted
+    /// connection before handing it to `handle_connection`.
+    pub async fn bind_tls(addr: &str, tls: TlsConfig) -> std::io::Result<Self> {
+        let acceptor = tls.build_acceptor()?;
+        let mut server = Self::bind(addr).await?;
+        server.tls_acceptor = Some(acceptor);
+        Ok(server)
+    }
+
+    /// Bind as with `bind`, but first restore the keyspace from `data_dir`
+    /// (snapshot + command log replay) and keep persisting writes there,
+    /// snapshotting automatically every 1000 writes or 5 minutes.
+    pub async fn bind_with_data_dir(addr: &str, data_dir: &str) -> std::io::Result<Self> {
+        let server = Self::bind(addr).await?;
+
+        let persistence = Arc::new(Persistence::new(data_dir, 1000));
+        persistence
+            .restore(server.handle.store())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        server.handle.store().attach_persistence(persistence.clone());
+        persistence.spawn_periodic_snapshot(server.handle.store().clone(), Duration::from_secs(300));
+
+        Ok(server)
+    }
+
     /// Get local listening address
     pub fn local_addr(&self) -> SocketAddr {
         self.local_addr
     }
 
-    /// Start server, accept and process connections
+    /// Start server, accept and process connections until the process is
+    /// killed. Never drains gracefully; callers that need a clean shutdown
+    /// should use `run_until` with a `watch` channel instead.
     pub async fn run(&self) {
+        let (_never_fires, shutdown_rx) = watch::channel(false);
+        self.run_until(shutdown_rx).await;
+    }
+
+    /// Accept and process connections until `shutdown` is set to `true`, then
+    /// stop accepting new connections, signal every in-flight connection to
+    /// finish up at its next frame boundary, and wait up to
+    /// `SHUTDOWN_DRAIN_TIMEOUT` for them to do so before giving up and
+    /// returning anyway.
+    pub async fn run_until(&self, mut shutdown: watch::Receiver<bool>) {
         info!("Server started, listening on {}", self.local_addr);
 
         loop {
-            match self.listener.accept().await {
-                Ok((stream, peer_addr)) => {
-                    info!("New connection accepted from {}", peer_addr);
+            tokio::select! {
+                biased;
 
-                    // Clone the handle for the new connection
-                    let handle = self.handle.clone();
+                _ = shutdown.changed() => {
+                    if *shutdown.borrow() {
+                        info!("Shutdown requested, no longer accepting new connections");
+                        break;
+                    }
+                }
+
+                accepted = self.listener.accept() => {
+                    match accepted {
+                        Ok((stream, peer_addr)) => {
+                            info!("New connection accepted from {}", peer_addr);
 
-                    // Spawn an independent task for each connection
-                    tokio::spawn(async move {
-                        if let Err(e) = handle.handle_connection(stream, peer_addr).await {
-                            error!("Error handling connection from {}: {}", peer_addr, e);
+                            // Clone the handle for the new connection
+                            let handle = self.handle.clone();
+                            let conn_shutdown = shutdown.clone();
+                            let active_connections = self.active_connections.clone();
+                            active_connections.fetch_add(1, Ordering::SeqCst);
+
+                            match self.tls_acceptor.clone() {
+                                Some(acceptor) => {
+                                    // Negotiate the TLS handshake inside the spawned task
+                                    // so a slow or stalled handshake can't block accept().
+                                    tokio::spawn(async move {
+                                        match acceptor.accept(stream).await {
+                                            Ok(tls_stream) => {
+                                                if let Err(e) = handle
+                                                    .handle_connection(tls_stream, peer_addr, conn_shutdown)
+                                                    .await
+                                                {
+                                                    error!(
+                                                        "Error handling TLS connection from {}: {}",
+                                                        peer_addr, e
+                                                    );
+                                                }
+                                            }
+                                            Err(e) => {
+                                                warn!(
+                                                    "TLS handshake failed for {}: {}, dropping connection",
+                                                    peer_addr, e
+                                                );
+                                            }
+                                        }
+                                        active_connections.fetch_sub(1, Ordering::SeqCst);
+                                    });
+                                }
+                                None => {
+                                    // Spawn an independent task for each connection
+                                    tokio::spawn(async move {
+                                        if let Err(e) = handle
+                                            .handle_connection(stream, peer_addr, conn_shutdown)
+                                            .await
+                                        {
+                                            error!("Error handling connection from {}: {}", peer_addr, e);
+                                        }
+                                        active_connections.fetch_sub(1, Ordering::SeqCst);
+                                    });
+                                }
+                            }
                         }
-                    });
-                }
-                Err(e) => {
-                    error!("Failed to accept connection: {}", e);
+                        Err(e) => {
+                            error!("Failed to accept connection: {}", e);
+                        }
+                    }
                 }
             }
         }
+
+        self.drain().await;
+    }
+
+    /// Wait for in-flight connections to finish on their own, up to
+    /// `SHUTDOWN_DRAIN_TIMEOUT`, then return regardless.
+    async fn drain(&self) {
+        let remaining = self.active_connections.clone();
+        let wait_for_drain = async {
+            while remaining.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        };
+
+        if tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, wait_for_drain)
+            .await
+            .is_err()
+        {
+            warn!(
+                "Shutdown drain timed out after {:?} with {} connection(s) still active",
+                SHUTDOWN_DRAIN_TIMEOUT,
+                self.active_connections.load(Ordering::SeqCst)
+            );
+        } else {
+            info!("All connections drained cleanly");
+        }
     }
 }
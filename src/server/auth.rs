@@ -0,0 +1,135 @@
+//! Pluggable authentication for the RESP `AUTH` command, so methods beyond
+//! a static password table (e.g. token-based auth) can be added later
+//! without touching command dispatch.
+
+use std::collections::HashMap;
+
+/// A way to verify `AUTH` credentials.
+pub trait Authenticator: Send + Sync {
+  /// Verify a username/password pair (`username` is `None` for the legacy
+  /// `AUTH password` form).
+  fn authenticate(&self, username: Option<&str>, password: &str) -> bool;
+
+  /// Whether this authenticator requires anything at all. When `false`, new
+  /// connections start already authenticated.
+  fn is_enabled(&self) -> bool;
+}
+
+/// Authenticator backed by `Config`'s `requirepass` and `users` table.
+pub struct ConfigAuthenticator {
+  requirepass: Option<String>,
+  users: HashMap<String, String>,
+}
+
+impl ConfigAuthenticator {
+  pub fn new(requirepass: Option<String>, users: HashMap<String, String>) -> Self {
+    Self {
+      requirepass,
+      users,
+    }
+  }
+}
+
+impl Authenticator for ConfigAuthenticator {
+  fn authenticate(&self, username: Option<&str>, password: &str) -> bool {
+    match username {
+      Some(user) => self.users.get(user).map(|p| p == password).unwrap_or(false),
+      None => self.requirepass.as_deref() == Some(password),
+    }
+  }
+
+  fn is_enabled(&self) -> bool {
+    self.requirepass.is_some() || !self.users.is_empty()
+  }
+}
+
+/// RESP protocol version a connection starts on before it sends `HELLO`.
+const DEFAULT_PROTOCOL_VERSION: u8 = 2;
+
+/// Per-connection state owned by `handle_connection`: authentication plus the
+/// negotiated RESP protocol version (set via `HELLO`).
+pub struct ConnState {
+  authenticated: bool,
+  protocol: u8,
+}
+
+impl ConnState {
+  /// A connection starts authenticated iff no authenticator is configured,
+  /// and always starts on RESP2 until it sends `HELLO 3`.
+  pub fn new(authenticator: &dyn Authenticator) -> Self {
+    Self {
+      authenticated: !authenticator.is_enabled(),
+      protocol: DEFAULT_PROTOCOL_VERSION,
+    }
+  }
+
+  pub fn is_authenticated(&self) -> bool {
+    self.authenticated
+  }
+
+  pub fn set_authenticated(&mut self, authenticated: bool) {
+    self.authenticated = authenticated;
+  }
+
+  pub fn protocol(&self) -> u8 {
+    self.protocol
+  }
+
+  pub fn set_protocol(&mut self, protocol: u8) {
+    self.protocol = protocol;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_requirepass_only() {
+    let auth = ConfigAuthenticator::new(Some("secret".to_string()), HashMap::new());
+    assert!(auth.is_enabled());
+    assert!(auth.authenticate(None, "secret"));
+    assert!(!auth.authenticate(None, "wrong"));
+    assert!(!auth.authenticate(Some("anyone"), "secret"));
+  }
+
+  #[test]
+  fn test_users_table() {
+    let mut users = HashMap::new();
+    users.insert("alice".to_string(), "wonderland".to_string());
+    let auth = ConfigAuthenticator::new(None, users);
+    assert!(auth.is_enabled());
+    assert!(auth.authenticate(Some("alice"), "wonderland"));
+    assert!(!auth.authenticate(Some("alice"), "wrong"));
+    assert!(!auth.authenticate(Some("bob"), "wonderland"));
+  }
+
+  #[test]
+  fn test_disabled_when_unconfigured() {
+    let auth = ConfigAuthenticator::new(None, HashMap::new());
+    assert!(!auth.is_enabled());
+  }
+
+  #[test]
+  fn test_conn_state_starts_authenticated_when_disabled() {
+    let auth = ConfigAuthenticator::new(None, HashMap::new());
+    let conn = ConnState::new(&auth);
+    assert!(conn.is_authenticated());
+  }
+
+  #[test]
+  fn test_conn_state_starts_unauthenticated_when_enabled() {
+    let auth = ConfigAuthenticator::new(Some("secret".to_string()), HashMap::new());
+    let conn = ConnState::new(&auth);
+    assert!(!conn.is_authenticated());
+  }
+
+  #[test]
+  fn test_conn_state_starts_on_resp2() {
+    let auth = ConfigAuthenticator::new(None, HashMap::new());
+    let mut conn = ConnState::new(&auth);
+    assert_eq!(conn.protocol(), 2);
+    conn.set_protocol(3);
+    assert_eq!(conn.protocol(), 3);
+  }
+}
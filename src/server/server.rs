@@ -1,21 +1,119 @@
+mod auth;
+
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig as RustlsServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::Message;
 use tracing::{error, info, warn};
 
-use crate::config::Config;
-use crate::protocol::{CommandFactory, Parser, Value};
+use crate::config::{ConfigHandle, TlsSection};
+use crate::protocol::{Parser, Value};
+use auth::{Authenticator, ConfigAuthenticator, ConnState};
+
+/// Size of each write when streaming a bulk string at or above
+/// `Config::streaming_threshold` out to a connection.
+const STREAMING_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Build a `TlsAcceptor` from a `[tls]` config section. When `client_ca_path`
+/// is set, client certificates are required and verified against that CA
+/// bundle (mTLS); otherwise only the server authenticates itself.
+fn build_acceptor(tls: &TlsSection) -> std::io::Result<TlsAcceptor> {
+  let certs = load_certs(&tls.cert_path)?;
+  let key = load_private_key(&tls.key_path)?;
+
+  let builder = RustlsServerConfig::builder();
+  let server_config = match &tls.client_ca_path {
+    Some(ca_path) => {
+      let roots = load_root_store(ca_path)?;
+      let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+        .build()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+      builder
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(certs, key)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?
+    }
+    None => builder
+      .with_no_client_auth()
+      .with_single_cert(certs, key)
+      .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+  };
+
+  Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn load_certs(path: impl AsRef<Path>) -> std::io::Result<Vec<CertificateDer<'static>>> {
+  let file = std::fs::File::open(path)?;
+  let mut reader = std::io::BufReader::new(file);
+  rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()
+}
+
+fn load_private_key(path: impl AsRef<Path>) -> std::io::Result<PrivateKeyDer<'static>> {
+  let file = std::fs::File::open(path)?;
+  let mut reader = std::io::BufReader::new(file);
+  rustls_pemfile::private_key(&mut reader)?
+    .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "no private key found"))
+}
+
+fn load_root_store(path: impl AsRef<Path>) -> std::io::Result<RootCertStore> {
+  let mut roots = RootCertStore::empty();
+  for cert in load_certs(path)? {
+    roots
+      .add(cert)
+      .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+  }
+  Ok(roots)
+}
+
+/// Collect the string form of every bulk/simple-string argument, dropping any
+/// that aren't one of those two types.
+fn bulk_args(items: &[Value]) -> Vec<String> {
+  items
+    .iter()
+    .filter_map(|item| match item {
+      Value::BulkString(Some(data)) => Some(String::from_utf8_lossy(data).to_string()),
+      Value::SimpleString(s) => Some(s.clone()),
+      _ => None,
+    })
+    .collect()
+}
+
+/// One voter or learner in the Raft membership, as reported by `CLUSTER
+/// MEMBERS`.
+struct ClusterMember {
+  node_id: u64,
+  addr: String,
+  voter: bool,
+}
 
 /// TCP server with Raft support
 pub struct Server {
   listener: TcpListener,
   local_addr: SocketAddr,
-  cmd_factory: Arc<CommandFactory>,
   /// Raft node for distributed consensus
   raft_node: Arc<rockraft::node::RaftNode>,
-  /// Server configuration
-  config: Config,
+  /// Live-reloadable server configuration; command handlers read through
+  /// this instead of capturing a config snapshot, so they see updates
+  /// applied by the config-watcher subsystem without a restart.
+  config: ConfigHandle,
+  /// TLS acceptor, present when `[tls]` is configured; every accepted
+  /// connection then terminates TLS (optionally requiring a client
+  /// certificate for mTLS) before any RESP parsing happens.
+  tls_acceptor: Option<TlsAcceptor>,
+  /// Verifies `AUTH` credentials; pluggable so future methods (e.g.
+  /// token-based) can be added without touching command dispatch.
+  authenticator: Arc<dyn Authenticator>,
+  /// Optional WebSocket listener, bound when `ws_addr` is configured. Carries
+  /// the same RESP-encoded commands as binary messages so browser/relay
+  /// clients that can only open WebSocket connections can still speak RESP.
+  ws_listener: Option<TcpListener>,
 }
 
 impl Server {
@@ -25,28 +123,48 @@ impl Server {
   /// 1. Creates and starts the Raft node
   /// 2. Binds the TCP server
   /// 3. Returns the initialized Server instance
-  pub async fn start(config: Config) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+  pub async fn start(config: ConfigHandle) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+    let initial = config.load();
+
     // Create and start Raft node
     info!("Creating Raft node...");
-    let raft_node = rockraft::node::RaftNodeBuilder::build(&config.raft)
+    let raft_node = rockraft::node::RaftNodeBuilder::build(&initial.raft)
       .await
       .map_err(|e| format!("Failed to create Raft node: {}", e))?;
     info!("Raft node created and started successfully");
 
     // Bind TCP server
-    let listener = TcpListener::bind(&config.server_addr).await?;
+    let listener = TcpListener::bind(&initial.server_addr).await?;
     let local_addr = listener.local_addr()?;
     info!("TCP server bound to {}", local_addr);
 
-    // Initialize command factory
-    let cmd_factory = Arc::new(CommandFactory::init());
+    let tls_acceptor = match &initial.tls {
+      Some(tls) => Some(build_acceptor(tls)?),
+      None => None,
+    };
+
+    let authenticator: Arc<dyn Authenticator> = Arc::new(ConfigAuthenticator::new(
+      initial.requirepass.clone(),
+      initial.users.clone(),
+    ));
+
+    let ws_listener = match &initial.ws_addr {
+      Some(addr) => {
+        let listener = TcpListener::bind(addr).await?;
+        info!("WebSocket server bound to {}", listener.local_addr()?);
+        Some(listener)
+      }
+      None => None,
+    };
 
     let server = Arc::new(Self {
       listener,
       local_addr,
-      cmd_factory,
       raft_node,
       config,
+      tls_acceptor,
+      authenticator,
+      ws_listener,
     });
 
     Ok(server)
@@ -97,19 +215,465 @@ impl Server {
     }
   }
 
-  /// Process a RESP command and return the response
+  /// Process a RESP data command and return the response. Unlike the plain
+  /// TCP server's `Store`-backed `CommandFactory`, every command here goes
+  /// through Raft consensus via `get`/`set`/`delete`, since this server's
+  /// state lives in the replicated log rather than a local `Store`.
   async fn process_command(&self, value: Value) -> Value {
-    self.cmd_factory.execute(value, self).await
+    let items = match &value {
+      Value::Array(Some(items)) if !items.is_empty() => items,
+      _ => return Value::error("ERR invalid command format"),
+    };
+
+    let name = match &items[0] {
+      Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_uppercase(),
+      Value::SimpleString(s) => s.to_uppercase(),
+      _ => return Value::error("ERR invalid command format"),
+    };
+
+    match name.as_str() {
+      "GET" => {
+        if items.len() != 2 {
+          return Value::error("ERR wrong number of arguments for 'get' command");
+        }
+        let key = match bulk_args(&items[1..2]).into_iter().next() {
+          Some(key) => key,
+          None => return Value::error("ERR wrong number of arguments for 'get' command"),
+        };
+        match self.get(&key).await {
+          Ok(value) => Value::BulkString(value),
+          Err(e) => Value::error(format!("ERR {}", e)),
+        }
+      }
+      "SET" => {
+        if items.len() != 3 {
+          return Value::error("ERR wrong number of arguments for 'set' command");
+        }
+        let key = match bulk_args(&items[1..2]).into_iter().next() {
+          Some(key) => key,
+          None => return Value::error("ERR wrong number of arguments for 'set' command"),
+        };
+        let value = match &items[2] {
+          Value::BulkString(Some(data)) => data.clone(),
+          Value::SimpleString(s) => s.as_bytes().to_vec(),
+          _ => return Value::error("ERR wrong number of arguments for 'set' command"),
+        };
+        match self.set(key, value).await {
+          Ok(()) => Value::ok(),
+          Err(e) => Value::error(format!("ERR {}", e)),
+        }
+      }
+      "DEL" => {
+        if items.len() != 2 {
+          return Value::error("ERR wrong number of arguments for 'del' command");
+        }
+        let key = match bulk_args(&items[1..2]).into_iter().next() {
+          Some(key) => key,
+          None => return Value::error("ERR wrong number of arguments for 'del' command"),
+        };
+        match self.delete(&key).await {
+          Ok(true) => Value::Integer(1),
+          Ok(false) => Value::Integer(0),
+          Err(e) => Value::error(format!("ERR {}", e)),
+        }
+      }
+      _ => Value::error(format!("ERR unknown command '{}'", name)),
+    }
+  }
+
+  /// Dispatch one parsed command, handling `AUTH` directly (since it mutates
+  /// this connection's local state) and gating every command but
+  /// `AUTH`/`HELLO`/`PING` behind authentication, then handling `CLUSTER` and
+  /// falling through to `process_command` for everything else.
+  async fn dispatch(&self, value: Value, conn: &mut ConnState) -> Value {
+    let items = match &value {
+      Value::Array(Some(items)) if !items.is_empty() => items.clone(),
+      _ => return self.process_command(value).await,
+    };
+
+    let name = match &items[0] {
+      Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_uppercase(),
+      Value::SimpleString(s) => s.to_uppercase(),
+      _ => return self.process_command(value).await,
+    };
+
+    if name == "HELLO" {
+      return self.hello(&items, conn);
+    }
+
+    if name == "AUTH" {
+      return self.auth(&items, conn);
+    }
+
+    if !conn.is_authenticated() && name != "HELLO" && name != "PING" {
+      return Value::error("NOAUTH Authentication required.");
+    }
+
+    if name == "CLUSTER" {
+      return self.cluster(&items).await;
+    }
+
+    self.process_command(value).await
+  }
+
+  /// Handle `HELLO [protover [AUTH user pass]]`: negotiate the connection's
+  /// RESP protocol version, optionally authenticating inline, and reply with
+  /// a server-info map (downgraded to a flat array on RESP2 connections).
+  fn hello(&self, items: &[Value], conn: &mut ConnState) -> Value {
+    let requested = match items.len() {
+      1 => conn.protocol(),
+      len if len >= 2 => match Self::parse_protover(&items[1]) {
+        Some(v) => v,
+        None => return Value::error("NOPROTO unsupported protocol version"),
+      },
+      _ => unreachable!(),
+    };
+
+    if items.len() > 2 {
+      if items.len() != 5 || !matches!(&items[2], Value::BulkString(Some(d)) if d.eq_ignore_ascii_case(b"AUTH"))
+      {
+        return Value::error("ERR syntax error in HELLO");
+      }
+      let auth_reply = self.auth(&items[2..], conn);
+      if let Value::Error(_) = auth_reply {
+        return auth_reply;
+      }
+    }
+
+    if !conn.is_authenticated() {
+      return Value::error("NOAUTH HELLO must be called with the client already authenticated, otherwise the HELLO <proto> AUTH <user> <pass> option can be used to authenticate the client and select the RESP protocol version at the same time");
+    }
+
+    conn.set_protocol(requested);
+
+    Value::Map(vec![
+      (
+        Value::BulkString(Some(b"server".to_vec())),
+        Value::BulkString(Some(b"coredb".to_vec())),
+      ),
+      (
+        Value::BulkString(Some(b"version".to_vec())),
+        Value::BulkString(Some(b"0.1.0".to_vec())),
+      ),
+      (
+        Value::BulkString(Some(b"proto".to_vec())),
+        Value::Integer(requested as i64),
+      ),
+      (
+        Value::BulkString(Some(b"id".to_vec())),
+        Value::Integer(0),
+      ),
+      (
+        Value::BulkString(Some(b"mode".to_vec())),
+        Value::BulkString(Some(b"cluster".to_vec())),
+      ),
+      (
+        Value::BulkString(Some(b"role".to_vec())),
+        Value::BulkString(Some(b"master".to_vec())),
+      ),
+      (
+        Value::BulkString(Some(b"modules".to_vec())),
+        Value::Array(Some(Vec::new())),
+      ),
+    ])
+  }
+
+  fn parse_protover(value: &Value) -> Option<u8> {
+    let text = match value {
+      Value::BulkString(Some(data)) => String::from_utf8_lossy(data).to_string(),
+      Value::SimpleString(s) => s.clone(),
+      _ => return None,
+    };
+    match text.as_str() {
+      "2" => Some(2),
+      "3" => Some(3),
+      _ => None,
+    }
+  }
+
+  /// Handle `AUTH [username] password`, flipping `conn` to authenticated on a
+  /// correct credential.
+  fn auth(&self, items: &[Value], conn: &mut ConnState) -> Value {
+    let args = bulk_args(&items[1..]);
+
+    let (username, password) = match args.len() {
+      1 => (None, args[0].clone()),
+      2 => (Some(args[0].clone()), args[1].clone()),
+      _ => return Value::error("ERR wrong number of arguments for 'auth' command"),
+    };
+
+    if !self.authenticator.is_enabled() {
+      return Value::error("ERR Client sent AUTH, but no password is set");
+    }
+
+    if self.authenticator.authenticate(username.as_deref(), &password) {
+      conn.set_authenticated(true);
+      Value::ok()
+    } else {
+      Value::error("WRONGPASS invalid username-password pair or user is disabled.")
+    }
+  }
+
+  /// Dispatch `CLUSTER INFO|MEMBERS|LEADER|ADD|REMOVE`, the admin commands
+  /// that expose Raft membership over the wire.
+  async fn cluster(&self, items: &[Value]) -> Value {
+    let args = bulk_args(&items[1..]);
+    let sub = match args.first() {
+      Some(s) => s.to_uppercase(),
+      None => return Value::error("ERR wrong number of arguments for 'cluster' command"),
+    };
+
+    match sub.as_str() {
+      "INFO" => self.cluster_info().await,
+      "MEMBERS" => self.cluster_members_reply().await,
+      "LEADER" => self.cluster_leader().await,
+      "ADD" => self.cluster_add(&args[1..]).await,
+      "REMOVE" => self.cluster_remove(&args[1..]).await,
+      _ => Value::error(format!("ERR unknown CLUSTER subcommand '{}'", sub)),
+    }
+  }
+
+  /// `CLUSTER INFO`: this node's role, term, and current leader id.
+  async fn cluster_info(&self) -> Value {
+    let term = self.raft_node.term().await;
+    let leader_id = self.raft_node.current_leader().await;
+    let role = if self.raft_node.is_leader().await {
+      "leader"
+    } else {
+      "follower"
+    };
+
+    Value::Map(vec![
+      (
+        Value::BulkString(Some(b"role".to_vec())),
+        Value::BulkString(Some(role.as_bytes().to_vec())),
+      ),
+      (
+        Value::BulkString(Some(b"term".to_vec())),
+        Value::Integer(term as i64),
+      ),
+      (
+        Value::BulkString(Some(b"leader_id".to_vec())),
+        match leader_id {
+          Some(id) => Value::Integer(id as i64),
+          None => Value::Null,
+        },
+      ),
+    ])
+  }
+
+  /// `CLUSTER MEMBERS`: every voter and learner, with its address.
+  async fn cluster_members_reply(&self) -> Value {
+    match self.cluster_members().await {
+      Ok(members) => Value::Array(Some(
+        members
+          .into_iter()
+          .map(|m| {
+            Value::Map(vec![
+              (
+                Value::BulkString(Some(b"node_id".to_vec())),
+                Value::Integer(m.node_id as i64),
+              ),
+              (
+                Value::BulkString(Some(b"addr".to_vec())),
+                Value::BulkString(Some(m.addr.into_bytes())),
+              ),
+              (
+                Value::BulkString(Some(b"role".to_vec())),
+                Value::BulkString(Some(
+                  if m.voter { b"voter".to_vec() } else { b"learner".to_vec() },
+                )),
+              ),
+            ])
+          })
+          .collect(),
+      )),
+      Err(e) => Value::error(format!("ERR failed to read cluster membership: {}", e)),
+    }
   }
 
-  /// Handle a single client connection
-  async fn handle_connection(
+  async fn cluster_members(&self) -> Result<Vec<ClusterMember>, String> {
+    self
+      .raft_node
+      .members()
+      .await
+      .map(|members| {
+        members
+          .into_iter()
+          .map(|(node_id, addr, voter)| ClusterMember {
+            node_id,
+            addr,
+            voter,
+          })
+          .collect()
+      })
+      .map_err(|e| format!("Failed to read cluster membership: {}", e))
+  }
+
+  /// `CLUSTER LEADER`: the current leader's node id and address, if known.
+  async fn cluster_leader(&self) -> Value {
+    let leader_id = match self.raft_node.current_leader().await {
+      Some(id) => id,
+      None => return Value::error("CLUSTERDOWN No leader elected"),
+    };
+
+    let members = match self.cluster_members().await {
+      Ok(members) => members,
+      Err(e) => return Value::error(format!("ERR {}", e)),
+    };
+
+    match members.into_iter().find(|m| m.node_id == leader_id) {
+      Some(m) => Value::Map(vec![
+        (
+          Value::BulkString(Some(b"node_id".to_vec())),
+          Value::Integer(m.node_id as i64),
+        ),
+        (
+          Value::BulkString(Some(b"addr".to_vec())),
+          Value::BulkString(Some(m.addr.into_bytes())),
+        ),
+      ]),
+      None => Value::error("CLUSTERDOWN Leader address unknown"),
+    }
+  }
+
+  /// `CLUSTER ADD <node_id> <addr>`: propose adding a new member through
+  /// consensus. Rejected with `-MOVED` when this node isn't the leader.
+  async fn cluster_add(&self, args: &[String]) -> Value {
+    if !self.raft_node.is_leader().await {
+      return self.moved_to_leader().await;
+    }
+
+    let (node_id, addr) = match args {
+      [node_id, addr] => match node_id.parse::<u64>() {
+        Ok(id) => (id, addr.clone()),
+        Err(_) => return Value::error("ERR node_id must be an integer"),
+      },
+      _ => return Value::error("ERR wrong number of arguments for 'cluster add' command"),
+    };
+
+    match self.raft_node.add_member(node_id, addr).await {
+      Ok(()) => Value::ok(),
+      Err(e) => Value::error(format!("ERR failed to add cluster member: {}", e)),
+    }
+  }
+
+  /// `CLUSTER REMOVE <node_id>`: propose removing a member through
+  /// consensus. Rejected with `-MOVED` when this node isn't the leader.
+  async fn cluster_remove(&self, args: &[String]) -> Value {
+    if !self.raft_node.is_leader().await {
+      return self.moved_to_leader().await;
+    }
+
+    let node_id = match args {
+      [node_id] => match node_id.parse::<u64>() {
+        Ok(id) => id,
+        Err(_) => return Value::error("ERR node_id must be an integer"),
+      },
+      _ => return Value::error("ERR wrong number of arguments for 'cluster remove' command"),
+    };
+
+    match self.raft_node.remove_member(node_id).await {
+      Ok(()) => Value::ok(),
+      Err(e) => Value::error(format!("ERR failed to remove cluster member: {}", e)),
+    }
+  }
+
+  /// Build a `-MOVED` redirect pointing at the current leader's address, for
+  /// membership-change commands issued against a follower.
+  async fn moved_to_leader(&self) -> Value {
+    let leader_id = match self.raft_node.current_leader().await {
+      Some(id) => id,
+      None => return Value::error("CLUSTERDOWN No leader elected"),
+    };
+
+    let members = match self.cluster_members().await {
+      Ok(members) => members,
+      Err(e) => return Value::error(format!("ERR {}", e)),
+    };
+
+    match members.into_iter().find(|m| m.node_id == leader_id) {
+      Some(m) => Value::error(format!("MOVED {}", m.addr)),
+      None => Value::error("CLUSTERDOWN Leader address unknown"),
+    }
+  }
+
+  /// Parse and dispatch every complete command currently sitting in
+  /// `pending`, returning one un-encoded response per command. Shared by the
+  /// byte-stream (`handle_connection`) and message-stream (`handle_ws_connection`)
+  /// framings so both carry commands through the same dispatch core; left
+  /// un-encoded so `write_response` can stream large bulk strings instead of
+  /// materializing the whole encoded frame up front.
+  async fn process_frame(&self, pending: &mut Vec<u8>, conn: &mut ConnState) -> Vec<Value> {
+    let mut responses = Vec::new();
+    let mut processed = 0;
+
+    loop {
+      match Parser::parse(&pending[processed..]) {
+        Some((value, consumed)) => {
+          processed += consumed;
+          info!("Received command: {:?}", value);
+          responses.push(self.dispatch(value, conn).await);
+        }
+        None => break,
+      }
+    }
+
+    if processed > 0 {
+      *pending = pending.split_off(processed);
+    }
+
+    responses
+  }
+
+  /// Write one response to `stream`. Bulk strings at or above
+  /// `streaming_threshold` are written as a length header followed by the
+  /// payload in fixed-size chunks, so a single large `GET` response is never
+  /// fully materialized as one encoded `Vec<u8>`; every other value is small
+  /// enough that the existing single-shot `encode_resp` is simpler and
+  /// sufficient.
+  ///
+  /// Inbound commands are not streamed: a `SET` value is buffered in
+  /// `pending` until its frame is complete and is then written through `set`
+  /// as one Raft log entry.
+  async fn write_response<S>(
+    &self,
+    stream: &mut S,
+    response: &Value,
+    protocol: u8,
+  ) -> std::io::Result<()>
+  where
+    S: AsyncWrite + Unpin,
+  {
+    if let Value::BulkString(Some(data)) = response {
+      let threshold = self.config.load().streaming_threshold;
+      if data.len() >= threshold {
+        stream
+          .write_all(format!("${}\r\n", data.len()).as_bytes())
+          .await?;
+        for chunk in data.chunks(STREAMING_CHUNK_SIZE) {
+          stream.write_all(chunk).await?;
+        }
+        return stream.write_all(b"\r\n").await;
+      }
+    }
+
+    stream.write_all(&response.encode_resp(protocol)).await
+  }
+
+  /// Handle a single client connection over any byte stream (plaintext TCP
+  /// or a negotiated TLS/mTLS stream).
+  async fn handle_connection<S>(
     self: Arc<Self>,
-    mut stream: TcpStream,
+    mut stream: S,
     peer_addr: SocketAddr,
-  ) -> std::io::Result<()> {
+  ) -> std::io::Result<()>
+  where
+    S: AsyncRead + AsyncWrite + Unpin,
+  {
     let mut buffer = vec![0u8; 8192]; // 8KB buffer
     let mut pending = Vec::new(); // Buffer for incomplete commands
+    let mut conn = ConnState::new(self.authenticator.as_ref());
 
     loop {
       match stream.read(&mut buffer).await {
@@ -118,40 +682,15 @@ impl Server {
           break;
         }
         Ok(n) => {
-          // Append new data to pending buffer
           pending.extend_from_slice(&buffer[..n]);
 
-          // Try to parse and process complete commands
-          let mut processed = 0;
-          loop {
-            match Parser::parse(&pending[processed..]) {
-              Some((value, consumed)) => {
-                processed += consumed;
-
-                // Log the parsed command
-                info!("Received command from {}: {:?}", peer_addr, value);
-
-                // Process the command and get response
-                let response = self.process_command(value).await;
-                let encoded = response.encode();
-
-                // Send response
-                if let Err(e) = stream.write_all(&encoded).await {
-                  warn!("Failed to write response to {}: {}", peer_addr, e);
-                  break;
-                }
-              }
-              None => {
-                // No complete command available
-                break;
-              }
+          for response in self.process_frame(&mut pending, &mut conn).await {
+            let protocol = conn.protocol();
+            if let Err(e) = self.write_response(&mut stream, &response, protocol).await {
+              warn!("Failed to write response to {}: {}", peer_addr, e);
+              break;
             }
           }
-
-          // Remove processed data from pending buffer
-          if processed > 0 {
-            pending = pending.split_off(processed);
-          }
         }
         Err(e) => {
           error!("Error reading from {}: {}", peer_addr, e);
@@ -164,34 +703,132 @@ impl Server {
     Ok(())
   }
 
+  /// Handle a single client connection over a WebSocket, carrying the same
+  /// RESP-encoded commands as binary messages so clients that can only open
+  /// WebSocket connections (e.g. browsers behind an HTTP reverse proxy) can
+  /// still speak RESP.
+  async fn handle_ws_connection(self: Arc<Self>, stream: TcpStream, peer_addr: SocketAddr) {
+    let ws_stream = match tokio_tungstenite::accept_async(stream).await {
+      Ok(ws) => ws,
+      Err(e) => {
+        warn!("WebSocket handshake failed for {}: {}", peer_addr, e);
+        return;
+      }
+    };
+
+    let (mut ws_write, mut ws_read) = ws_stream.split();
+    let mut pending = Vec::new();
+    let mut conn = ConnState::new(self.authenticator.as_ref());
+
+    while let Some(message) = ws_read.next().await {
+      let data = match message {
+        Ok(Message::Binary(data)) => data,
+        Ok(Message::Close(_)) => {
+          info!("WebSocket connection closed by client: {}", peer_addr);
+          break;
+        }
+        Ok(_) => continue,
+        Err(e) => {
+          warn!("Error reading WebSocket frame from {}: {}", peer_addr, e);
+          break;
+        }
+      };
+
+      pending.extend_from_slice(&data);
+
+      for response in self.process_frame(&mut pending, &mut conn).await {
+        let encoded = response.encode_resp(conn.protocol());
+        if let Err(e) = ws_write.send(Message::Binary(encoded)).await {
+          warn!("Failed to write WebSocket response to {}: {}", peer_addr, e);
+          break;
+        }
+      }
+    }
+
+    info!("WebSocket connection handler ended for {}", peer_addr);
+  }
+
   /// Start server, accept and process connections
   pub async fn run(self: Arc<Self>) {
+    let config = self.config.load();
     info!("Server started, listening on {}", self.local_addr);
-    info!("Raft node ID: {}", self.config.raft.node_id);
-    info!("Raft address: {}", self.config.raft.raft.address);
+    info!("Raft node ID: {}", config.raft.node_id);
+    info!("Raft address: {}", config.raft.raft.address);
 
     loop {
-      match self.listener.accept().await {
-        Ok((stream, peer_addr)) => {
-          info!("New connection accepted from {}", peer_addr);
+      tokio::select! {
+        accepted = self.listener.accept() => {
+          match accepted {
+            Ok((stream, peer_addr)) => {
+              info!("New connection accepted from {}", peer_addr);
 
-          // Clone the Arc<Server> for the new connection
-          let server = Arc::clone(&self);
+              // Clone the Arc<Server> for the new connection
+              let server = Arc::clone(&self);
 
-          // Spawn an independent task for each connection
-          tokio::spawn(async move {
-            if let Err(e) = server.handle_connection(stream, peer_addr).await {
-              error!("Error handling connection from {}: {}", peer_addr, e);
+              match self.tls_acceptor.clone() {
+                Some(acceptor) => {
+                  // Negotiate the TLS handshake inside the spawned task so a
+                  // slow or stalled handshake can't block accept().
+                  tokio::spawn(async move {
+                    match acceptor.accept(stream).await {
+                      Ok(tls_stream) => {
+                        if let Err(e) = server.handle_connection(tls_stream, peer_addr).await {
+                          error!("Error handling TLS connection from {}: {}", peer_addr, e);
+                        }
+                      }
+                      Err(e) => {
+                        warn!(
+                          "TLS handshake failed for {}: {}, dropping connection",
+                          peer_addr, e
+                        );
+                      }
+                    }
+                  });
+                }
+                None => {
+                  // Spawn an independent task for each connection
+                  tokio::spawn(async move {
+                    if let Err(e) = server.handle_connection(stream, peer_addr).await {
+                      error!("Error handling connection from {}: {}", peer_addr, e);
+                    }
+                  });
+                }
+              }
             }
-          });
+            Err(e) => {
+              error!("Failed to accept connection: {}", e);
+            }
+          }
         }
-        Err(e) => {
-          error!("Failed to accept connection: {}", e);
+
+        accepted = Self::accept_ws(&self.ws_listener) => {
+          match accepted {
+            Ok((stream, peer_addr)) => {
+              info!("New WebSocket connection accepted from {}", peer_addr);
+              let server = Arc::clone(&self);
+              tokio::spawn(async move {
+                server.handle_ws_connection(stream, peer_addr).await;
+              });
+            }
+            Err(e) => {
+              error!("Failed to accept WebSocket connection: {}", e);
+            }
+          }
         }
       }
     }
   }
 
+  /// Accept on the optional WebSocket listener, or never resolve if it isn't
+  /// configured, so the `select!` arm in `run` is a no-op branch when
+  /// `ws_addr` wasn't set.
+  async fn accept_ws(listener: &Option<TcpListener>) -> std::io::Result<(TcpStream, SocketAddr)> {
+    match listener {
+      Some(listener) => listener.accept().await,
+      None => std::future::pending().await,
+    }
+  }
+
   /// Shutdown the server and Raft node
   pub async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
     info!("Shutting down Raft node...");
@@ -1,30 +1,543 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::RwLock;
+use std::time::Duration;
 
-/// In-memory key-value store
+use tracing::debug;
+
+use crate::encoding::StringValue;
+use crate::persistence::{LogEntry, Persistence};
+use crate::pubsub::PubSub;
+use crate::util::now_ms;
+use std::sync::Arc;
+
+/// Default maxmemory budget (64 MiB) used when no explicit limit is configured.
+const DEFAULT_MAXMEMORY: usize = 64 * 1024 * 1024;
+
+/// Number of keys sampled per active-expiration sweep.
+const ACTIVE_EXPIRE_SAMPLE_SIZE: usize = 20;
+
+/// Re-sweep immediately if more than this fraction of the sample was expired.
+const ACTIVE_EXPIRE_REPEAT_THRESHOLD: f64 = 0.25;
+
+/// Eviction policy applied once the store exceeds its memory budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evict the least-recently-used key regardless of TTL.
+    AllKeysLru,
+    /// Never evict. Writes are still accepted once the budget is exceeded,
+    /// so `used_memory` can grow past `maxmemory`.
+    NoEviction,
+}
+
+/// A single stored entry plus the bookkeeping needed for LRU eviction.
+struct Entry {
+    value: Vec<u8>,
+    /// Monotonically increasing access counter; larger means more recent.
+    last_accessed: u64,
+}
+
+impl Entry {
+    fn memory_size(key: &str, value: &[u8]) -> usize {
+        key.len() + value.len() + std::mem::size_of::<Entry>()
+    }
+}
+
+/// Outcome of `Store::set_conditional`.
+pub struct ConditionalSet {
+    /// The key's previous raw value, if it was present and unexpired.
+    pub previous: Option<Vec<u8>>,
+    /// Whether the new value was actually written (`false` when an `NX`/`XX`
+    /// precondition skipped the write).
+    pub applied: bool,
+}
+
+/// In-memory key-value store with LRU + memory-budget eviction and TTL expiration
 pub struct Store {
-    data: RwLock<HashMap<String, Vec<u8>>>,
+    data: RwLock<HashMap<String, Entry>>,
+    /// Approximate memory footprint of `data`, in bytes.
+    used_memory: AtomicU64,
+    /// Memory budget; once exceeded, `set` triggers eviction.
+    maxmemory: usize,
+    /// Policy used to pick eviction victims once over budget.
+    eviction_policy: EvictionPolicy,
+    /// Monotonic clock driving the LRU recency ordering.
+    clock: AtomicU64,
+    /// Publish/subscribe registry, independent of the keyspace above.
+    pubsub: PubSub,
+    /// Optional snapshot + append-only log subsystem; absent when the store
+    /// was created without a data directory (e.g. in tests).
+    persistence: RwLock<Option<Arc<Persistence>>>,
 }
 
 impl Store {
-    /// Create a new empty store
+    /// Create a new empty store with the default maxmemory budget.
     pub fn new() -> Self {
+        Self::with_config(DEFAULT_MAXMEMORY, EvictionPolicy::AllKeysLru)
+    }
+
+    /// Create a new empty store with an explicit maxmemory budget and eviction policy.
+    pub fn with_config(maxmemory: usize, eviction_policy: EvictionPolicy) -> Self {
         Self {
             data: RwLock::new(HashMap::new()),
+            used_memory: AtomicU64::new(0),
+            maxmemory,
+            eviction_policy,
+            clock: AtomicU64::new(0),
+            pubsub: PubSub::new(),
+            persistence: RwLock::new(None),
         }
     }
 
-    /// Set a key to the given value
+    /// Access the publish/subscribe registry shared by this store's connections.
+    pub fn pubsub(&self) -> &PubSub {
+        &self.pubsub
+    }
+
+    /// Attach a persistence subsystem; once attached, every mutation is
+    /// appended to its command log (and may trigger a background snapshot).
+    pub fn attach_persistence(&self, persistence: Arc<Persistence>) {
+        *self.persistence.write().expect("lock poisoned") = Some(persistence);
+    }
+
+    /// The attached persistence subsystem, if any.
+    pub fn persistence(&self) -> Option<Arc<Persistence>> {
+        self.persistence.read().expect("lock poisoned").clone()
+    }
+
+    fn record_write(&self, entry: LogEntry) {
+        let persistence = self.persistence.read().expect("lock poisoned").clone();
+        if let Some(persistence) = persistence {
+            persistence.record_write(self, entry);
+        }
+    }
+
+    /// Insert an already-encoded `StringValue` directly, bypassing
+    /// re-encoding. Used to restore entries from a snapshot or log replay,
+    /// and does not itself append to the command log.
+    pub fn load_encoded(&self, key: String, encoded: Vec<u8>) {
+        let added_size = Entry::memory_size(&key, &encoded) as u64;
+        let mut data = match self.data.write() {
+            Ok(data) => data,
+            Err(_) => return,
+        };
+        let old_size = data
+            .get(&key)
+            .map(|e| Entry::memory_size(&key, &e.value) as u64)
+            .unwrap_or(0);
+        self.used_memory.fetch_add(added_size, Ordering::Relaxed);
+        self.used_memory.fetch_sub(old_size, Ordering::Relaxed);
+
+        let last_accessed = self.tick();
+        data.insert(
+            key,
+            Entry {
+                value: encoded,
+                last_accessed,
+            },
+        );
+    }
+
+    /// Snapshot every live (non-expired) entry as `(key, encoded StringValue)`
+    /// pairs, suitable for `persistence::save_snapshot`.
+    pub fn snapshot_entries(&self) -> Vec<(String, Vec<u8>)> {
+        let now = now_ms();
+        let data = match self.data.read() {
+            Ok(data) => data,
+            Err(_) => return Vec::new(),
+        };
+        data.iter()
+            .filter_map(|(key, entry)| {
+                let decoded = StringValue::deserialize(&entry.value).ok()?;
+                if decoded.is_expired(now) {
+                    None
+                } else {
+                    Some((key.clone(), entry.value.clone()))
+                }
+            })
+            .collect()
+    }
+
+    /// Configured maxmemory budget, in bytes.
+    pub fn maxmemory(&self) -> usize {
+        self.maxmemory
+    }
+
+    /// Approximate memory currently used by stored entries, in bytes.
+    pub fn used_memory(&self) -> u64 {
+        self.used_memory.load(Ordering::Relaxed)
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Set a key to the given raw value (no expiration).
     pub fn set(&self, key: String, value: Vec<u8>) -> Result<(), String> {
-        let mut data = self.data.write().map_err(|_| "Lock poisoned")?;
-        data.insert(key, value);
+        self.set_with_expiry(key, value, None)
+    }
+
+    /// Set a key to the given raw value with an optional expiration timestamp
+    /// (in milliseconds since the Unix epoch).
+    pub fn set_with_expiry(
+        &self,
+        key: String,
+        value: Vec<u8>,
+        expires_at: Option<u64>,
+    ) -> Result<(), String> {
+        let string_value = match expires_at {
+            Some(exp) => StringValue::with_expiration(value, exp),
+            None => StringValue::new(value),
+        };
+        let encoded = string_value.serialize();
+        let added_size = Entry::memory_size(&key, &encoded) as u64;
+
+        {
+            let mut data = self.data.write().map_err(|_| "Lock poisoned")?;
+
+            let old_size = data
+                .get(&key)
+                .map(|e| Entry::memory_size(&key, &e.value) as u64)
+                .unwrap_or(0);
+
+            self.used_memory.fetch_add(added_size, Ordering::Relaxed);
+            self.used_memory.fetch_sub(old_size, Ordering::Relaxed);
+
+            let last_accessed = self.tick();
+            data.insert(
+                key.clone(),
+                Entry {
+                    value: encoded.clone(),
+                    last_accessed,
+                },
+            );
+
+            self.evict_over_budget(&mut data);
+        }
+
+        self.record_write(LogEntry::Set { key, encoded });
         Ok(())
     }
 
-    /// Get the value for a key (not used yet, but will be needed for GET)
+    /// Atomically read-modify-write a key for `SET`'s `NX`/`XX`/`GET`/`KEEPTTL`
+    /// options: `nx`/`xx` gate whether the write happens at all (based on
+    /// whether the key is currently present), `keep_ttl` carries the previous
+    /// entry's expiration forward instead of applying `expires_at`, and the
+    /// previous raw value is always returned so callers implementing `GET`
+    /// can report it. Holds the write lock for the whole operation so the
+    /// read-decide-write is linearizable against concurrent callers.
+    pub fn set_conditional(
+        &self,
+        key: String,
+        value: Vec<u8>,
+        nx: bool,
+        xx: bool,
+        keep_ttl: bool,
+        expires_at: Option<u64>,
+    ) -> Result<ConditionalSet, String> {
+        let mut data = self.data.write().map_err(|_| "Lock poisoned")?;
+
+        let now = now_ms();
+        let previous = match data.get(&key) {
+            Some(entry) => {
+                let decoded = StringValue::deserialize(&entry.value).map_err(|e| e.to_string())?;
+                if decoded.is_expired(now) {
+                    None
+                } else {
+                    Some(decoded)
+                }
+            }
+            None => None,
+        };
+
+        let exists = previous.is_some();
+        if (nx && exists) || (xx && !exists) {
+            return Ok(ConditionalSet {
+                previous: previous.map(|p| p.data),
+                applied: false,
+            });
+        }
+
+        let expires_at = if keep_ttl {
+            previous.as_ref().and_then(|p| p.expires_at)
+        } else {
+            expires_at
+        };
+
+        let string_value = match expires_at {
+            Some(exp) => StringValue::with_expiration(value, exp),
+            None => StringValue::new(value),
+        };
+        let encoded = string_value.serialize();
+        let added_size = Entry::memory_size(&key, &encoded) as u64;
+
+        let old_size = data
+            .get(&key)
+            .map(|e| Entry::memory_size(&key, &e.value) as u64)
+            .unwrap_or(0);
+        self.used_memory.fetch_add(added_size, Ordering::Relaxed);
+        self.used_memory.fetch_sub(old_size, Ordering::Relaxed);
+
+        let last_accessed = self.tick();
+        data.insert(
+            key.clone(),
+            Entry {
+                value: encoded.clone(),
+                last_accessed,
+            },
+        );
+
+        self.evict_over_budget(&mut data);
+        drop(data);
+
+        self.record_write(LogEntry::Set { key, encoded });
+
+        Ok(ConditionalSet {
+            previous: previous.map(|p| p.data),
+            applied: true,
+        })
+    }
+
+    /// Get the value for a key, performing passive expiration: an expired
+    /// entry is deleted and `None` is returned as if it never existed.
     pub fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        // Fast path: read lock, bump recency, check expiration.
+        {
+            let data = self.data.read().map_err(|_| "Lock poisoned")?;
+            match data.get(key) {
+                Some(entry) => {
+                    let decoded = StringValue::deserialize(&entry.value)
+                        .map_err(|e| e.to_string())?;
+                    if !decoded.is_expired(now_ms()) {
+                        let last_accessed = self.tick();
+                        // Upgrade isn't possible while holding the read guard;
+                        // record the access, then bump recency under a write lock.
+                        drop(data);
+                        if let Ok(mut data) = self.data.write() {
+                            if let Some(entry) = data.get_mut(key) {
+                                entry.last_accessed = last_accessed;
+                            }
+                        }
+                        return Ok(Some(decoded.data));
+                    }
+                }
+                None => return Ok(None),
+            }
+        }
+
+        // Entry was present but expired: delete it and report a miss.
+        let mut data = self.data.write().map_err(|_| "Lock poisoned")?;
+        if let Some(entry) = data.remove(key) {
+            let size = Entry::memory_size(key, &entry.value) as u64;
+            self.used_memory.fetch_sub(size, Ordering::Relaxed);
+        }
+        Ok(None)
+    }
+
+    /// Remove a key unconditionally. Returns whether it was present.
+    pub fn remove(&self, key: &str) -> Result<bool, String> {
+        let removed = {
+            let mut data = self.data.write().map_err(|_| "Lock poisoned")?;
+            match data.remove(key) {
+                Some(entry) => {
+                    let size = Entry::memory_size(key, &entry.value) as u64;
+                    self.used_memory.fetch_sub(size, Ordering::Relaxed);
+                    true
+                }
+                None => false,
+            }
+        };
+
+        if removed {
+            self.record_write(LogEntry::Delete {
+                key: key.to_string(),
+            });
+        }
+        Ok(removed)
+    }
+
+    /// Evict least-recently-used entries until `used_memory` is back under budget.
+    fn evict_over_budget(&self, data: &mut HashMap<String, Entry>) {
+        if self.eviction_policy == EvictionPolicy::NoEviction {
+            return;
+        }
+
+        while self.used_memory.load(Ordering::Relaxed) as usize > self.maxmemory {
+            let victim = data
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_accessed)
+                .map(|(k, _)| k.clone());
+
+            match victim {
+                Some(key) => {
+                    if let Some(entry) = data.remove(&key) {
+                        let size = Entry::memory_size(&key, &entry.value) as u64;
+                        self.used_memory.fetch_sub(size, Ordering::Relaxed);
+                        debug!("Evicted key '{}' ({} bytes) to stay under maxmemory", key, size);
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Sample up to `ACTIVE_EXPIRE_SAMPLE_SIZE` keys and delete the expired
+    /// ones. Returns the number of keys removed. Used by the active-expiry
+    /// background task.
+    pub fn active_expire_cycle(&self) -> Result<usize, String> {
+        let now = now_ms();
+        let expired_keys: Vec<String> = {
+            let data = self.data.read().map_err(|_| "Lock poisoned")?;
+            data.iter()
+                .take(ACTIVE_EXPIRE_SAMPLE_SIZE)
+                .filter_map(|(key, entry)| {
+                    match StringValue::deserialize(&entry.value) {
+                        Ok(decoded) if decoded.is_expired(now) => Some(key.clone()),
+                        _ => None,
+                    }
+                })
+                .collect()
+        };
+
+        let sampled = ACTIVE_EXPIRE_SAMPLE_SIZE.min(
+            self.data
+                .read()
+                .map_err(|_| "Lock poisoned")?
+                .len(),
+        );
+
+        let mut data = self.data.write().map_err(|_| "Lock poisoned")?;
+        for key in &expired_keys {
+            if let Some(entry) = data.remove(key) {
+                let size = Entry::memory_size(key, &entry.value) as u64;
+                self.used_memory.fetch_sub(size, Ordering::Relaxed);
+            }
+        }
+        drop(data);
+
+        let removed = expired_keys.len();
+        if sampled > 0 && removed as f64 / sampled as f64 > ACTIVE_EXPIRE_REPEAT_THRESHOLD {
+            // More than a quarter of the sample was stale; keep sweeping.
+            let more = self.active_expire_cycle()?;
+            return Ok(removed + more);
+        }
+
+        Ok(removed)
+    }
+
+    /// List every live (non-expired) key matching a glob `pattern`
+    /// (`*`, `?`, `[...]`). Unlike `scan`, this walks the whole keyspace in
+    /// one call.
+    pub fn keys(&self, pattern: &str) -> Result<Vec<String>, String> {
+        let now = now_ms();
         let data = self.data.read().map_err(|_| "Lock poisoned")?;
-        Ok(data.get(key).cloned())
+        Ok(data
+            .iter()
+            .filter(|(key, entry)| {
+                crate::pubsub::glob_match(pattern, key)
+                    && StringValue::deserialize(&entry.value)
+                        .map(|v| !v.is_expired(now))
+                        .unwrap_or(false)
+            })
+            .map(|(key, _)| key.clone())
+            .collect())
+    }
+
+    /// Delete every key in `keys`, returning how many were actually present.
+    pub fn delete_many(&self, keys: &[String]) -> Result<usize, String> {
+        let mut deleted = 0;
+        for key in keys {
+            if self.remove(key)? {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Incrementally scan the keyspace using a Redis-style reverse-binary-
+    /// increment cursor, so iteration stays stable (every key present for the
+    /// whole scan is returned at least once) even if the table is rehashed
+    /// (here: resized) between calls. Cursor `0` means both "start" and
+    /// "iteration complete"; pass the returned cursor back in to continue.
+    pub fn scan(
+        &self,
+        cursor: u64,
+        pattern: Option<&str>,
+        count: usize,
+    ) -> Result<(u64, Vec<String>), String> {
+        let now = now_ms();
+        let data = self.data.read().map_err(|_| "Lock poisoned")?;
+
+        if data.is_empty() {
+            return Ok((0, Vec::new()));
+        }
+
+        let nbuckets = data.len().max(1).next_power_of_two() as u64;
+        let mask = nbuckets - 1;
+
+        // Group live keys by which logical bucket they'd occupy in a table
+        // of size `nbuckets`, using a stable hash of the key.
+        let mut buckets: HashMap<u64, Vec<&String>> = HashMap::new();
+        for (key, entry) in data.iter() {
+            let expired = StringValue::deserialize(&entry.value)
+                .map(|v| v.is_expired(now))
+                .unwrap_or(true);
+            if expired {
+                continue;
+            }
+            let bucket = bucket_index(key) & mask;
+            buckets.entry(bucket).or_default().push(key);
+        }
+
+        let count = count.max(1);
+        let mut collected = Vec::new();
+        let mut next = cursor & mask;
+        let mut visited = 0u64;
+
+        loop {
+            if let Some(keys_in_bucket) = buckets.get(&next) {
+                for key in keys_in_bucket {
+                    match pattern {
+                        Some(p) if !crate::pubsub::glob_match(p, key) => {}
+                        _ => collected.push((*key).clone()),
+                    }
+                }
+            }
+
+            visited += 1;
+            next = reverse_binary_increment(next, mask);
+
+            if next == 0 {
+                // Wrapped back around: the whole keyspace has been visited.
+                return Ok((0, collected));
+            }
+            if collected.len() >= count || visited > nbuckets {
+                break;
+            }
+        }
+
+        Ok((next, collected))
+    }
+
+    /// Spawn a background task that periodically runs `active_expire_cycle`.
+    pub fn spawn_active_expire(self: std::sync::Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.active_expire_cycle() {
+                    Ok(removed) if removed > 0 => {
+                        debug!("Active expiration swept {} expired key(s)", removed);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        debug!("Active expiration cycle failed: {}", e);
+                    }
+                }
+            }
+        });
     }
 }
 
@@ -33,3 +546,102 @@ impl Default for Store {
         Self::new()
     }
 }
+
+fn bucket_index(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Advance a Redis-style scan cursor: reverse the bits within `mask`'s width,
+/// increment, then reverse back. This visits every bucket exactly once over
+/// a full cycle while tolerating the table doubling/halving mid-scan.
+fn reverse_binary_increment(cursor: u64, mask: u64) -> u64 {
+    let bits = (mask.max(1) + 1).trailing_zeros();
+    let mut v = cursor | !mask;
+    v = reverse_bits(v, bits);
+    v = v.wrapping_add(1);
+    reverse_bits(v, bits)
+}
+
+fn reverse_bits(mut v: u64, bits: u32) -> u64 {
+    let mut result = 0u64;
+    for _ in 0..bits {
+        result = (result << 1) | (v & 1);
+        v >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_glob_match() {
+        let store = Store::new();
+        store.set("foo:1".to_string(), b"a".to_vec()).unwrap();
+        store.set("foo:2".to_string(), b"b".to_vec()).unwrap();
+        store.set("bar:1".to_string(), b"c".to_vec()).unwrap();
+
+        let mut matched = store.keys("foo:*").unwrap();
+        matched.sort();
+        assert_eq!(matched, vec!["foo:1".to_string(), "foo:2".to_string()]);
+    }
+
+    #[test]
+    fn test_delete_many() {
+        let store = Store::new();
+        store.set("a".to_string(), b"1".to_vec()).unwrap();
+        store.set("b".to_string(), b"2".to_vec()).unwrap();
+
+        let deleted = store
+            .delete_many(&["a".to_string(), "missing".to_string(), "b".to_string()])
+            .unwrap();
+        assert_eq!(deleted, 2);
+        assert_eq!(store.get("a").unwrap(), None);
+    }
+
+    #[test]
+    fn test_scan_visits_every_key_exactly_once_per_cycle() {
+        let store = Store::new();
+        let mut expected: Vec<String> = Vec::new();
+        for i in 0..50 {
+            let key = format!("key:{}", i);
+            store.set(key.clone(), b"v".to_vec()).unwrap();
+            expected.push(key);
+        }
+        expected.sort();
+
+        let mut cursor = 0u64;
+        let mut seen = Vec::new();
+        loop {
+            let (next, batch) = store.scan(cursor, None, 10).unwrap();
+            seen.extend(batch);
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        seen.sort();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn test_scan_empty_store_completes_immediately() {
+        let store = Store::new();
+        let (cursor, batch) = store.scan(0, None, 10).unwrap();
+        assert_eq!(cursor, 0);
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn test_passive_expiration_on_get() {
+        let store = Store::new();
+        store
+            .set_with_expiry("k".to_string(), b"v".to_vec(), Some(1))
+            .unwrap();
+        // expires_at=1ms is always in the past relative to now_ms()
+        assert_eq!(store.get("k").unwrap(), None);
+    }
+}